@@ -1,7 +1,7 @@
 'use client'
 
 import React, { useState, useEffect } from 'react'
-import { BettingMarketService, Market, UserBet, Odds } from '../lib/betting-market'
+import { BettingMarketService, Market, UserBet, isResolved, outcomeLabel } from '../lib/betting-market'
 import { WalletService } from '../lib/wallet'
 import { ASSETS } from './AssetPriceDisplay'
 import { useTelegramNotifications } from '../lib/useTelegramNotifications'
@@ -11,6 +11,10 @@ interface BettingMarketProps {
   contractAddress: string
 }
 
+// Outcome indices of the UP / DOWN / STABLE band markets created from this dashboard
+const PREDICTION_OUTCOMES = { Up: 0, Down: 1, Stable: 2 } as const
+const OUTCOME_COLORS = ['text-green-600', 'text-red-600', 'text-blue-600']
+
 export default function BettingMarketDashboard({ contractAddress }: BettingMarketProps) {
   const [service] = useState(() => new BettingMarketService(contractAddress))
   const [walletService] = useState(() => WalletService.getInstance())
@@ -22,7 +26,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
   const [resolvedMarkets, setResolvedMarkets] = useState<Market[]>([])
   const [selectedMarket, setSelectedMarket] = useState<Market | null>(null)
   const [userBet, setUserBet] = useState<UserBet | null>(null)
-  const [odds, setOdds] = useState<Odds | null>(null)
+  const [odds, setOdds] = useState<bigint[] | null>(null)
   const [currentPrice, setCurrentPrice] = useState<bigint | null>(null)
   const [loading, setLoading] = useState(false)
   const [resolvedMarketsLoading, setResolvedMarketsLoading] = useState(false)
@@ -102,7 +106,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
       const allMarketsData: Array<{
         id: number
         title?: string
-        is_resolved?: boolean
+        resolved?: boolean
         exists: boolean
         error?: string
       }> = [] // For debugging
@@ -130,11 +134,11 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                 allMarketsData.push({
                   id: marketId,
                   title: market.title,
-                  is_resolved: market.is_resolved,
+                  resolved: isResolved(market),
                   exists: true
                 })
                 
-                if (market.is_resolved) {
+                if (isResolved(market)) {
                   resolvedMarketsData.push(market)
                   console.log('✅ HISTORY: Found resolved market:', marketId, market.title)
                 } else {
@@ -187,8 +191,8 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
       console.log('🔧 HISTORY: All markets debug info:', allMarketsData)
       const existingMarkets = allMarketsData.filter(m => m.exists)
       const missingMarkets = allMarketsData.filter(m => !m.exists)
-      const resolvedCount = existingMarkets.filter(m => m.is_resolved).length
-      const activeCount = existingMarkets.filter(m => !m.is_resolved).length
+      const resolvedCount = existingMarkets.filter(m => m.resolved).length
+      const activeCount = existingMarkets.filter(m => !m.resolved).length
       
       console.log('🔧 HISTORY: Summary:')
       console.log('  - Total markets checked:', allMarketsData.length)
@@ -260,13 +264,13 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
       setOdds(marketOdds)
       
       // Load current price
-      const price = await service.getCurrentPrice(market.token)
+      const price = await service.getCurrentPrice(market.feed_asset)
       setCurrentPrice(price)
       
-      // Load user bet if connected
+      // Load user bet if connected - the dashboard shows the user's first position
       if (userAddress) {
-        const bet = await service.getUserBet(market.id, userAddress)
-        setUserBet(bet)
+        const positions = await service.getUserPositions(market.id, userAddress)
+        setUserBet(positions[0] ?? null)
       }
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to load market details')
@@ -324,7 +328,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
         userAddress,
         selectedMarket.id,
         amount,
-        selectedPrediction
+        PREDICTION_OUTCOMES[selectedPrediction]
       )
       
       if (result.success) {
@@ -368,11 +372,12 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
         newMarket.title,
         newMarket.token,
         newMarket.bettingToken,
-        BettingMarketService.parseAmount(newMarket.targetPrice),
-        BettingMarketService.parseAmount(newMarket.stableTolerance),
-        BigInt(newMarket.durationHours),
-        false, // auto_restart
-        BigInt(newMarket.houseEdge)
+        { durationHours: BigInt(newMarket.durationHours) },
+        {
+          targetPrice: BettingMarketService.parseAmount(newMarket.targetPrice),
+          stableTolerance: BettingMarketService.parseAmount(newMarket.stableTolerance),
+          houseEdge: BigInt(newMarket.houseEdge)
+        }
       )
       
       if (result.success) {
@@ -418,33 +423,10 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
     return new Date(Number(timestamp) * 1000).toLocaleString()
   }
 
-  // Format winning side
-  const formatWinningSide = (side: number | string | undefined) => {
-    if (side === undefined || side === null) return 'Unknown'
-    
-    // Handle different formats
-    const sideStr = side.toString()
-    switch (sideStr) {
-      case '0': return 'Up'
-      case '1': return 'Down' 
-      case '2': return 'Stable'
-      case 'Up': return 'Up'
-      case 'Down': return 'Down'
-      case 'Stable': return 'Stable'
-      default: return sideStr
-    }
-  }
-
   // Get winner count for resolved market
   const getWinnerCount = (market: Market) => {
-    if (!market.is_resolved || market.winning_side === undefined) return 0
-    
-    switch (market.winning_side) {
-      case 'Up': return market.up_betters_count
-      case 'Down': return market.down_betters_count  
-      case 'Stable': return market.stable_betters_count
-      default: return 0
-    }
+    if (!isResolved(market) || market.winning_outcome === undefined) return 0
+    return market.outcomes[market.winning_outcome]?.betters_count ?? 0
   }
 
   // Calculate time remaining
@@ -471,18 +453,18 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
     addDebugLog(`Checking for expired markets at ${new Date().toISOString()}`)
     
     for (const market of markets) {
-      if (!market.is_resolved && Number(market.end_time) <= now) {
+      if (!isResolved(market) && Number(market.end_time) <= now) {
         try {
           addDebugLog(`Found expired market: ${market.title} (ID: ${market.id})`)
           setSuccess(`Auto-resolving expired market: ${market.title}`)
           
           // Get current price
-          addDebugLog(`Getting current price for token: ${market.token}`)
-          const currentPrice = await service.getCurrentPrice(market.token)
+          addDebugLog(`Getting current price for token: ${market.feed_asset}`)
+          const currentPrice = await service.getCurrentPrice(market.feed_asset)
           
           // Check if price is available
           if (currentPrice === null) {
-            const errorMsg = `Failed to get current price for market ${market.id} (token: ${market.token})`
+            const errorMsg = `Failed to get current price for market ${market.id} (token: ${market.feed_asset})`
             addDebugLog(`ERROR: ${errorMsg}`)
             setError(errorMsg)
             continue
@@ -544,17 +526,15 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
           addDebugLog(`Resolve result for market ${market.id} (after ${retryCount} retries): ${JSON.stringify(result)}`)
           
           if (result && result.success) {
-            const winningSide = result.winningSide ? parseInt(result.winningSide) : 0
-            const winnerText = winningSide === 0 ? 'UP' : winningSide === 1 ? 'DOWN' : 'STABLE'
+            const winningOutcome = result.winningOutcome ?? 0
+            const winnerText = outcomeLabel(market, winningOutcome)
             const successMsg = `Market "${market.title}" auto-resolved! Winner: ${winnerText}${retryCount > 0 ? ` (succeeded after ${retryCount} retries)` : ''}`
             addDebugLog(`SUCCESS: ${successMsg}`)
             setSuccess(successMsg)
             
             // Send telegram notification for resolved market
             try {
-              const winnerCount = winningSide === 0 ? market.up_betters_count : 
-                                winningSide === 1 ? market.down_betters_count : 
-                                market.stable_betters_count
+              const winnerCount = market.outcomes[winningOutcome]?.betters_count ?? 0
               
               // Direct API call to ensure userId is properly passed
               const telegramResponse = await fetch('/api/telegram', {
@@ -904,7 +884,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                       className={`border rounded-lg p-4 cursor-pointer transition-colors ${
                         selectedMarket?.id === market.id 
                           ? 'border-blue-500 bg-blue-50' 
-                          : isExpired && !market.is_resolved
+                          : isExpired && !isResolved(market)
                             ? 'border-orange-500 bg-orange-50 hover:border-orange-600'
                             : 'border-gray-200 hover:border-gray-300'
                       }`}
@@ -917,7 +897,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                         
                         {/* Show time info based on view mode */}
                         {viewMode === 'active' ? (
-                          <p className={isExpired && !market.is_resolved ? 'text-orange-600 font-medium' : ''}>
+                          <p className={isExpired && !isResolved(market) ? 'text-orange-600 font-medium' : ''}>
                             Time Left: {timeRemaining}
                           </p>
                         ) : (
@@ -927,10 +907,10 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                         )}
                         
                         {/* Show winner info for resolved markets */}
-                        {viewMode === 'resolved' && market.is_resolved && (
+                        {viewMode === 'resolved' && isResolved(market) && (
                           <div className="mt-2 p-2 bg-green-50 rounded border border-green-200">
                             <p className="text-green-800 font-medium text-xs">
-                              🏆 Winning Side: {formatWinningSide(market.winning_side)}
+                              🏆 Winning Side: {outcomeLabel(market, market.winning_outcome)}
                             </p>
                             <p className="text-green-700 text-xs">
                               👥 Number of Winners: {getWinnerCount(market)} person
@@ -945,7 +925,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                         )}
                         
                         {/* Auto-resolve status */}
-                        {isExpired && !market.is_resolved && (
+                        {isExpired && !isResolved(market) && (
                           <p className="text-orange-600 text-xs font-medium">
                             🤖 Auto-resolving...
                           </p>
@@ -953,17 +933,17 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                         
                         <div className="flex justify-between">
                           <span className={`px-2 py-1 rounded text-xs ${
-                            market.is_resolved 
+                            isResolved(market) 
                               ? 'bg-green-100 text-green-800' 
                               : isExpired 
                                 ? 'bg-orange-100 text-orange-800'
                                 : 'bg-yellow-100 text-yellow-800'
                           }`}>
-                            {market.is_resolved ? 'Resolved' : isExpired ? 'Expired' : 'Active'}
+                            {isResolved(market) ? 'Resolved' : isExpired ? 'Expired' : 'Active'}
                           </span>
-                          {market.winning_side !== undefined && market.winning_side !== null && (
+                          {market.winning_outcome !== undefined && (
                             <span className="px-2 py-1 rounded text-xs bg-blue-100 text-blue-800">
-                              Winner: {formatWinningSide(market.winning_side)}
+                              Winner: {outcomeLabel(market, market.winning_outcome)}
                             </span>
                           )}
                         </div>
@@ -986,7 +966,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
         {selectedMarket && (() => {
           const now = Math.floor(Date.now() / 1000)
           const isExpired = Number(selectedMarket.end_time) <= now
-          const isActive = !selectedMarket.is_resolved && !isExpired
+          const isActive = !isResolved(selectedMarket) && !isExpired
           
           // If market is not active (resolved or expired), show simple status
           if (!isActive) {
@@ -994,7 +974,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
               <div className="bg-gray-50 rounded-lg shadow p-6">
                 <div className="text-center">
                   <h2 className="text-xl font-semibold mb-4 text-gray-700">
-                    {selectedMarket.is_resolved ? '✅ Market Resolved' : '⏰ Market Expired'}
+                    {isResolved(selectedMarket) ? '✅ Market Resolved' : '⏰ Market Expired'}
                   </h2>
                   <div className="space-y-2">
                     <p className="text-gray-600">
@@ -1002,13 +982,13 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                     </p>
                     <p className="text-gray-600">
                       <span className="font-medium">Status:</span> {
-                        selectedMarket.is_resolved ? 'Resolved' : 'Expired (Auto-resolving...)'
+                        isResolved(selectedMarket) ? 'Resolved' : 'Expired (Auto-resolving...)'
                       }
                     </p>
-                    {selectedMarket.is_resolved && (
+                    {isResolved(selectedMarket) && (
                       <>
                         <p className="text-green-600 font-medium">
-                          🏆 Winner: {formatWinningSide(selectedMarket.winning_side)}
+                          🏆 Winner: {outcomeLabel(selectedMarket, selectedMarket.winning_outcome)}
                         </p>
                         <p className="text-gray-600">
                           <span className="font-medium">Final Price:</span> {
@@ -1053,33 +1033,25 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                 <div className="border-t pt-3 mt-4">
                   <h3 className="font-medium mb-2">Betting Stats</h3>
                   <div className="grid grid-cols-3 gap-2 text-sm">
-                    <div className="text-center">
-                      <div className="font-medium text-green-600">UP</div>
-                      <div>{BettingMarketService.formatAmount(selectedMarket.total_up_bets)}</div>
-                      <div>({selectedMarket.up_betters_count} bets)</div>
-                    </div>
-                    <div className="text-center">
-                      <div className="font-medium text-red-600">DOWN</div>
-                      <div>{BettingMarketService.formatAmount(selectedMarket.total_down_bets)}</div>
-                      <div>({selectedMarket.down_betters_count} bets)</div>
-                    </div>
-                    <div className="text-center">
-                      <div className="font-medium text-blue-600">STABLE</div>
-                      <div>{BettingMarketService.formatAmount(selectedMarket.total_stable_bets)}</div>
-                      <div>({selectedMarket.stable_betters_count} bets)</div>
-                    </div>
+                    {selectedMarket.outcomes.map((pool, index) => (
+                      <div key={pool.label} className="text-center">
+                        <div className={`font-medium ${OUTCOME_COLORS[index % OUTCOME_COLORS.length]}`}>{pool.label}</div>
+                        <div>{BettingMarketService.formatAmount(pool.total_bets)}</div>
+                        <div>({pool.betters_count} bets)</div>
+                      </div>
+                    ))}
                   </div>
                 </div>
 
                 {/* Winner Info for Resolved Markets */}
-                {selectedMarket.is_resolved && (
+                {isResolved(selectedMarket) && (
                   <div className="border-t pt-3 mt-4">
                     <h3 className="font-medium mb-2 text-green-800">🏆 Market Results</h3>
                     <div className="bg-green-50 rounded p-3 space-y-2">
                       <div className="flex justify-between">
                         <span className="text-sm font-medium">Winning Side:</span>
                         <span className="text-sm text-green-700 font-bold">
-                          {formatWinningSide(selectedMarket.winning_side)}
+                          {outcomeLabel(selectedMarket, selectedMarket.winning_outcome)}
                         </span>
                       </div>
                       <div className="flex justify-between">
@@ -1097,7 +1069,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                       <div className="flex justify-between">
                         <span className="text-sm font-medium">Total Participants:</span>
                         <span className="text-sm text-gray-600">
-                          {selectedMarket.up_betters_count + selectedMarket.down_betters_count + selectedMarket.stable_betters_count} person
+                          {selectedMarket.outcomes.reduce((total, pool) => total + pool.betters_count, 0)} person
                         </span>
                       </div>
                     </div>
@@ -1109,24 +1081,20 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                   <div className="border-t pt-3 mt-4">
                     <h3 className="font-medium mb-2">Current Odds</h3>
                     <div className="grid grid-cols-3 gap-2 text-sm">
-                      <div className="text-center">
-                        <div className="font-medium text-green-600">UP</div>
-                        <div>{BettingMarketService.formatOdds(odds.up_odds)}</div>
-                      </div>
-                      <div className="text-center">
-                        <div className="font-medium text-red-600">DOWN</div>
-                        <div>{BettingMarketService.formatOdds(odds.down_odds)}</div>
-                      </div>
-                      <div className="text-center">
-                        <div className="font-medium text-blue-600">STABLE</div>
-                        <div>{BettingMarketService.formatOdds(odds.stable_odds)}</div>
-                      </div>
+                      {odds.map((outcomeOdds, index) => (
+                        <div key={index} className="text-center">
+                          <div className={`font-medium ${OUTCOME_COLORS[index % OUTCOME_COLORS.length]}`}>
+                            {outcomeLabel(selectedMarket, index)}
+                          </div>
+                          <div>{BettingMarketService.formatOdds(outcomeOdds)}</div>
+                        </div>
+                      ))}
                     </div>
                   </div>
                 )}
 
                 {/* Manual Resolve Button for Expired Markets */}
-                {!selectedMarket.is_resolved && getTimeRemaining(selectedMarket.end_time) === 'Expired' && isConnected && (
+                {!isResolved(selectedMarket) && getTimeRemaining(selectedMarket.end_time) === 'Expired' && isConnected && (
                   <div className="border-t pt-3 mt-4">
                     <button
                       onClick={async () => {
@@ -1137,7 +1105,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                           
                           addDebugLog(`Manual resolve started for market ${selectedMarket.id}`)
                           
-                          const currentPrice = await service.getCurrentPrice(selectedMarket.token)
+                          const currentPrice = await service.getCurrentPrice(selectedMarket.feed_asset)
                           
                           // Check if price is available
                           if (currentPrice === null) {
@@ -1205,17 +1173,15 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                           addDebugLog(`Manual resolve result: ${JSON.stringify(result)}`)
                           
                           if (result && result.success) {
-                            const winningSide = result.winningSide ? parseInt(result.winningSide) : 0
-                            const winnerText = winningSide === 0 ? 'UP' : winningSide === 1 ? 'DOWN' : 'STABLE'
+                            const winningOutcome = result.winningOutcome ?? 0
+                            const winnerText = outcomeLabel(selectedMarket, winningOutcome)
                             const successMsg = `Market resolved! Winner: ${winnerText}. Payouts distributed automatically.${retryCount > 0 ? ` (Succeeded after ${retryCount} retries)` : ''}`
                             addDebugLog(`SUCCESS: ${successMsg}`)
                             setSuccess(successMsg)
                             
                             // Send telegram notification for manually resolved market
                             try {
-                              const winnerCount = winningSide === 0 ? selectedMarket.up_betters_count : 
-                                                winningSide === 1 ? selectedMarket.down_betters_count : 
-                                                selectedMarket.stable_betters_count
+                              const winnerCount = selectedMarket.outcomes[winningOutcome]?.betters_count ?? 0
                               
                               // Direct API call to ensure userId is properly passed
                               const telegramResponse = await fetch('/api/telegram', {
@@ -1296,11 +1262,11 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                 <div className="mb-4 p-3 bg-blue-50 rounded border">
                   <h3 className="font-medium text-blue-800 mb-2">Your Current Bet</h3>
                   <div className="text-sm space-y-1">
-                    <p>Prediction: <span className="font-medium">{userBet.prediction}</span></p>
+                    <p>Prediction: <span className="font-medium">{outcomeLabel(selectedMarket, userBet.outcome)}</span></p>
                     <p>Amount: <span className="font-medium">{BettingMarketService.formatAmount(userBet.amount, 7)}</span></p>
                     <p>Odds when placed: <span className="font-medium">{BettingMarketService.formatOdds(userBet.odds_when_placed)}</span></p>
                     <p>Status: <span className={`font-medium ${
-                      !selectedMarket.is_resolved 
+                      !isResolved(selectedMarket) 
                         ? 'text-blue-600' 
                         : userBet.is_paid_out && userBet.winnings > 0
                           ? 'text-green-600'
@@ -1308,7 +1274,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                             ? 'text-red-600'
                             : 'text-orange-600'
                     }`}>
-                      {!selectedMarket.is_resolved 
+                      {!isResolved(selectedMarket) 
                         ? 'Active' 
                         : userBet.is_paid_out 
                           ? userBet.winnings > 0 
@@ -1317,7 +1283,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                           : 'Pending Payout'
                       }
                     </span></p>
-                    {selectedMarket.is_resolved && userBet.is_paid_out && (
+                    {isResolved(selectedMarket) && userBet.is_paid_out && (
                       <>
                         {userBet.winnings > 0 ? (
                           <p>Winnings: <span className="font-medium text-green-600">
@@ -1330,7 +1296,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                         )}
                       </>
                     )}
-                    {selectedMarket.is_resolved && !userBet.is_paid_out && (
+                    {isResolved(selectedMarket) && !userBet.is_paid_out && (
                       <p className="text-orange-600 text-sm">
                         ⏳ Payout processing...
                       </p>
@@ -1339,7 +1305,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                 </div>
               )}
 
-              {!selectedMarket.is_resolved && !userBet && isConnected && (() => {
+              {!isResolved(selectedMarket) && !userBet && isConnected && (() => {
                 // Check time remaining for betting eligibility
                 const now = Math.floor(Date.now() / 1000)
                 const timeRemaining = Number(selectedMarket.end_time) - now
@@ -1424,11 +1390,11 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                 <p className="text-gray-600 text-center">Connect wallet to place bets</p>
               )}
 
-              {selectedMarket.is_resolved && (
+              {isResolved(selectedMarket) && (
                 <div className="text-center">
                   <p className="text-lg font-medium text-gray-800 mb-2">Market Resolved</p>
                   <p className="text-green-600 font-medium">
-                    Winner: {selectedMarket.winning_side}
+                    Winner: {outcomeLabel(selectedMarket, selectedMarket.winning_outcome)}
                   </p>
                   <p className="text-sm text-gray-600">
                     Final Price: {selectedMarket.final_price ? BettingMarketService.formatAmount(selectedMarket.final_price) : 'N/A'}
@@ -1436,7 +1402,7 @@ export default function BettingMarketDashboard({ contractAddress }: BettingMarke
                 </div>
               )}
 
-              {userBet && !selectedMarket.is_resolved && (
+              {userBet && !isResolved(selectedMarket) && (
                 <p className="text-center text-gray-600">
                   You already have a bet on this market
                 </p>
@@ -0,0 +1,585 @@
+//! Market durum değişikliklerinin tipli event'leri - frontend ve bot marketleri yalnızca
+//! bu event'lerden indeksleyebilir.
+//!
+//! Topic: (event adı, EVENT_VERSION, market_id); ücret ve referans ödülü çekiminde market_id
+//! yerine token, seri olaylarında series_id, sezonda sezon id'si, rol değişikliğinde hesap,
+//! admin devrinde yeni admin, ayar değişikliğinde ayarı değiştiren admin.
+//! Payload'lar aşağıdaki `#[contracttype]` struct'lardır. Bir payload'ın şekli değişirse
+//! EVENT_VERSION artırılır; indeksleyiciler ikinci topic'e göre ayırt eder.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
+
+use crate::{
+    Dispute, DisputeConfig, Market, OutcomeRule, PayoutSummary, PriceFeed, PricingMode, Role, Season,
+    SettlementMode, UserBet, VoidReason,
+};
+
+pub const EVENT_VERSION: u32 = 2;
+
+const CREATED: Symbol = symbol_short!("MKT_NEW");
+const BET: Symbol = symbol_short!("BET");
+const CLOSED: Symbol = symbol_short!("CLOSED");
+const RESOLVED: Symbol = symbol_short!("RESOLVED");
+const PAYOUT: Symbol = symbol_short!("PAYOUT");
+const PAYOUT_FAILED: Symbol = symbol_short!("PAY_FAIL");
+const REFUND: Symbol = symbol_short!("REFUND");
+const SETTLED: Symbol = symbol_short!("SETTLED");
+const VOIDED: Symbol = symbol_short!("VOIDED");
+const FEE_WITHDRAWN: Symbol = symbol_short!("FEE_OUT");
+const REFERRED_BET: Symbol = symbol_short!("REF_BET");
+const REFERRAL_CLAIMED: Symbol = symbol_short!("REF_OUT");
+const LIQUIDITY_ADDED: Symbol = symbol_short!("LIQ_ADD");
+const LIQUIDITY_RETURNED: Symbol = symbol_short!("LIQ_OUT");
+const CASHED_OUT: Symbol = symbol_short!("CASHOUT");
+const TRANSFERRED: Symbol = symbol_short!("TRANSFER");
+const LISTED: Symbol = symbol_short!("LISTED");
+const SOLD: Symbol = symbol_short!("SOLD");
+const BANKROLL_ADDED: Symbol = symbol_short!("BANKROLL");
+const BANKROLL_WITHDRAWN: Symbol = symbol_short!("BANK_OUT");
+const SHARES_BOUGHT: Symbol = symbol_short!("BUY_SHR");
+const SHARES_SOLD: Symbol = symbol_short!("SELL_SHR");
+const DISPUTED: Symbol = symbol_short!("DISPUTE");
+const DISPUTE_SETTLED: Symbol = symbol_short!("DISP_END");
+const COMMISSION: Symbol = symbol_short!("COMMISSN");
+const ROUND: Symbol = symbol_short!("ROUND");
+const ROLE_GRANTED: Symbol = symbol_short!("ROLE_ADD");
+const ROLE_REVOKED: Symbol = symbol_short!("ROLE_DEL");
+const UNLISTED: Symbol = symbol_short!("UNLISTED");
+const SERIES_STOPPED: Symbol = symbol_short!("SER_STOP");
+const SEASON: Symbol = symbol_short!("SEASON");
+const ADMIN_TRANSFERRED: Symbol = symbol_short!("ADMIN");
+const CONFIG: Symbol = symbol_short!("CONFIG");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketCreatedEvent {
+    pub creator: Address,
+    pub title: String,
+    pub feed: PriceFeed,
+    pub betting_token: Address,
+    pub initial_price: i128, // Planlı marketlerde 0 - açılışta alınır
+    pub target_price: i128,
+    pub stable_tolerance: i128,
+    pub start_time: u64,
+    pub close_time: u64,
+    pub end_time: u64,
+    pub outcome_rule: OutcomeRule,
+    pub pricing: PricingMode,
+    pub settlement_mode: SettlementMode,
+    pub house_edge: i128,
+    pub series_id: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BetPlacedEvent {
+    pub user: Address,
+    pub outcome: u32,
+    pub amount: i128,
+    pub odds_when_placed: i128,    // Bahse kilitlenen / gösterilen oran (1000 = 1.0x)
+    pub odds: Vec<i128>,           // Bahis anında tüm outcome'ların oranları
+    pub outcome_totals: Vec<i128>, // Bahis sonrası havuzlar
+}
+
+/// Bahisler zamanla kapanır; event market bahisten çıktığında (çözümde) yayınlanır
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketClosedEvent {
+    pub close_time: u64,
+    pub total_volume: i128,
+    pub outcome_totals: Vec<i128>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketResolvedEvent {
+    pub winning_outcome: u32,
+    pub initial_price: i128,
+    pub final_price: i128,
+    pub settlement_mode: SettlementMode,
+    pub oracle_settled: bool, // final_price Reflector'dan mı, yoksa admin'in verdiği spot fiyat mı
+    pub price_timestamp: u64, // final_price'ın oracle kaydının zamanı; admin fiyatında 0
+    pub resolved_at: u64,
+    pub dispute_deadline: u64,
+}
+
+/// Kazanç, iade ya da başarısız ödeme - pozisyon başına
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionPaidEvent {
+    pub user: Address,
+    pub outcome: u32,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketVoidedEvent {
+    pub reason: VoidReason,
+    pub total_volume: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeWithdrawnEvent {
+    pub recipient: Address,
+    pub amount: i128,
+    pub creator_share: bool, // Creator komisyon payı mı, hazine mi
+    pub remaining: i128,     // Hazinede / creator'da kalan
+}
+
+/// Referanslı bahis - BET event'inin hemen ardından
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferredBetEvent {
+    pub user: Address,
+    pub referrer: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralClaimedEvent {
+    pub referrer: Address,
+    pub amount: i128,
+}
+
+/// Creator'ın tohum likiditesi - eklenirken outcome başına tutarlar, geri dönüşte tek tutar
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidityEvent {
+    pub provider: Address,
+    pub amounts: Vec<i128>,
+    pub total: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionTransferredEvent {
+    pub from: Address,
+    pub to: Address,
+    pub outcome: u32,
+}
+
+/// İlan açılışı (`buyer` yok) ya da satış
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionTradeEvent {
+    pub seller: Address,
+    pub buyer: Option<Address>,
+    pub outcome: u32,
+    pub price: i128,
+}
+
+/// Sabit oran / LMSR bankroll'u - yatırma ya da fazlanın çekimi sonrası kalan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BankrollEvent {
+    pub provider: Address,
+    pub amount: i128,
+    pub bankroll: i128,
+}
+
+/// LMSR pay alım/satımı - `amount` ödenen maliyet ya da alınan gelir
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SharesTradedEvent {
+    pub user: Address,
+    pub outcome: u32,
+    pub shares: i128,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeSettledEvent {
+    pub challenger: Address,
+    pub bond: i128,
+    pub overturned: bool, // true ise teminat iade edildi, değilse hazineye geçti
+    pub winning_outcome: u32,
+}
+
+/// Çözümde kesilen komisyonun dağılımı; kalan hazinenindir
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommissionEvent {
+    pub commission: i128,
+    pub creator_cut: i128,
+    pub referral_cut: i128,
+    pub treasury_cut: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundScheduledEvent {
+    pub market_id: u64,
+    pub start_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleChangedEvent {
+    pub role: Role,
+    pub admin: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ListingCancelledEvent {
+    pub seller: Address,
+    pub outcome: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeriesStoppedEvent {
+    pub creator: Address,
+    pub round_count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+}
+
+/// Admin ayarının yeni değeri
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigChange {
+    CreatorFeeShare(i128), // basis points
+    ReferralShare(i128),   // basis points
+    Dispute(DisputeConfig),
+}
+
+fn outcome_totals(env: &Env, market: &Market) -> Vec<i128> {
+    let mut totals = Vec::new(env);
+    for outcome in market.outcomes.iter() {
+        totals.push_back(outcome.total_bets);
+    }
+    totals
+}
+
+pub fn market_created(env: &Env, market: &Market) {
+    env.events().publish(
+        (CREATED, EVENT_VERSION, market.id),
+        MarketCreatedEvent {
+            creator: market.creator.clone(),
+            title: market.title.clone(),
+            feed: market.feed.clone(),
+            betting_token: market.betting_token.clone(),
+            initial_price: market.initial_price,
+            target_price: market.target_price,
+            stable_tolerance: market.stable_tolerance,
+            start_time: market.start_time,
+            close_time: market.close_time,
+            end_time: market.end_time,
+            outcome_rule: market.outcome_rule.clone(),
+            pricing: market.pricing.clone(),
+            settlement_mode: market.settlement_mode.clone(),
+            house_edge: market.house_edge,
+            series_id: market.series_id,
+        },
+    );
+}
+
+pub fn bet_placed(
+    env: &Env,
+    market: &Market,
+    user: &Address,
+    outcome: u32,
+    amount: i128,
+    odds_when_placed: i128,
+    odds: Vec<i128>,
+) {
+    env.events().publish(
+        (BET, EVENT_VERSION, market.id),
+        BetPlacedEvent {
+            user: user.clone(),
+            outcome,
+            amount,
+            odds_when_placed,
+            odds,
+            outcome_totals: outcome_totals(env, market),
+        },
+    );
+}
+
+pub fn market_closed(env: &Env, market: &Market) {
+    env.events().publish(
+        (CLOSED, EVENT_VERSION, market.id),
+        MarketClosedEvent {
+            close_time: market.close_time,
+            total_volume: market.total_volume(),
+            outcome_totals: outcome_totals(env, market),
+        },
+    );
+}
+
+pub fn market_resolved(env: &Env, market: &Market, oracle_settled: bool, price_timestamp: u64) {
+    env.events().publish(
+        (RESOLVED, EVENT_VERSION, market.id),
+        MarketResolvedEvent {
+            winning_outcome: market.winning_outcome.unwrap_or(0),
+            initial_price: market.initial_price,
+            final_price: market.final_price.unwrap_or(0),
+            settlement_mode: market.settlement_mode.clone(),
+            oracle_settled,
+            price_timestamp,
+            resolved_at: env.ledger().timestamp(),
+            dispute_deadline: market.dispute_deadline,
+        },
+    );
+}
+
+fn position_event(env: &Env, name: Symbol, position: &UserBet, amount: i128) {
+    env.events().publish(
+        (name, EVENT_VERSION, position.market_id),
+        PositionPaidEvent {
+            user: position.user.clone(),
+            outcome: position.outcome,
+            amount,
+        },
+    );
+}
+
+pub fn payout(env: &Env, position: &UserBet, amount: i128) {
+    position_event(env, PAYOUT, position, amount);
+}
+
+pub fn payout_failed(env: &Env, position: &UserBet, amount: i128) {
+    position_event(env, PAYOUT_FAILED, position, amount);
+}
+
+pub fn refund(env: &Env, position: &UserBet, amount: i128) {
+    position_event(env, REFUND, position, amount);
+}
+
+/// Toplu ödeme / iade turunun özeti
+pub fn settled(env: &Env, market_id: u64, summary: &PayoutSummary) {
+    env.events().publish((SETTLED, EVENT_VERSION, market_id), summary.clone());
+}
+
+pub fn market_voided(env: &Env, market: &Market) {
+    env.events().publish(
+        (VOIDED, EVENT_VERSION, market.id),
+        MarketVoidedEvent {
+            reason: market.void_reason,
+            total_volume: market.total_volume(),
+        },
+    );
+}
+
+pub fn fee_withdrawn(env: &Env, token: &Address, event: FeeWithdrawnEvent) {
+    env.events().publish((FEE_WITHDRAWN, EVENT_VERSION, token.clone()), event);
+}
+
+pub fn referred_bet(env: &Env, market_id: u64, user: &Address, referrer: &Address, amount: i128) {
+    env.events().publish(
+        (REFERRED_BET, EVENT_VERSION, market_id),
+        ReferredBetEvent {
+            user: user.clone(),
+            referrer: referrer.clone(),
+            amount,
+        },
+    );
+}
+
+pub fn referral_claimed(env: &Env, token: &Address, referrer: &Address, amount: i128) {
+    env.events().publish(
+        (REFERRAL_CLAIMED, EVENT_VERSION, token.clone()),
+        ReferralClaimedEvent {
+            referrer: referrer.clone(),
+            amount,
+        },
+    );
+}
+
+pub fn liquidity_added(env: &Env, market: &Market) {
+    env.events().publish(
+        (LIQUIDITY_ADDED, EVENT_VERSION, market.id),
+        LiquidityEvent {
+            provider: market.creator.clone(),
+            amounts: market.liquidity.clone(),
+            total: market.liquidity_total(),
+        },
+    );
+}
+
+pub fn liquidity_returned(env: &Env, market: &Market, amount: i128) {
+    env.events().publish(
+        (LIQUIDITY_RETURNED, EVENT_VERSION, market.id),
+        LiquidityEvent {
+            provider: market.creator.clone(),
+            amounts: Vec::new(env),
+            total: amount,
+        },
+    );
+}
+
+pub fn cashed_out(env: &Env, market_id: u64, user: &Address, outcome: u32, amount: i128) {
+    env.events().publish(
+        (CASHED_OUT, EVENT_VERSION, market_id),
+        PositionPaidEvent {
+            user: user.clone(),
+            outcome,
+            amount,
+        },
+    );
+}
+
+pub fn position_transferred(env: &Env, market_id: u64, from: &Address, to: &Address, outcome: u32) {
+    env.events().publish(
+        (TRANSFERRED, EVENT_VERSION, market_id),
+        PositionTransferredEvent {
+            from: from.clone(),
+            to: to.clone(),
+            outcome,
+        },
+    );
+}
+
+pub fn position_listed(env: &Env, market_id: u64, seller: &Address, outcome: u32, price: i128) {
+    env.events().publish(
+        (LISTED, EVENT_VERSION, market_id),
+        PositionTradeEvent {
+            seller: seller.clone(),
+            buyer: None,
+            outcome,
+            price,
+        },
+    );
+}
+
+pub fn position_sold(env: &Env, market_id: u64, seller: &Address, buyer: &Address, outcome: u32, price: i128) {
+    env.events().publish(
+        (SOLD, EVENT_VERSION, market_id),
+        PositionTradeEvent {
+            seller: seller.clone(),
+            buyer: Some(buyer.clone()),
+            outcome,
+            price,
+        },
+    );
+}
+
+pub fn bankroll_added(env: &Env, market: &Market, provider: &Address, amount: i128) {
+    env.events().publish(
+        (BANKROLL_ADDED, EVENT_VERSION, market.id),
+        BankrollEvent {
+            provider: provider.clone(),
+            amount,
+            bankroll: market.bankroll,
+        },
+    );
+}
+
+pub fn bankroll_withdrawn(env: &Env, market: &Market, provider: &Address, amount: i128) {
+    env.events().publish(
+        (BANKROLL_WITHDRAWN, EVENT_VERSION, market.id),
+        BankrollEvent {
+            provider: provider.clone(),
+            amount,
+            bankroll: market.bankroll,
+        },
+    );
+}
+
+fn shares_event(env: &Env, name: Symbol, market_id: u64, user: &Address, outcome: u32, shares: i128, amount: i128) {
+    env.events().publish(
+        (name, EVENT_VERSION, market_id),
+        SharesTradedEvent {
+            user: user.clone(),
+            outcome,
+            shares,
+            amount,
+        },
+    );
+}
+
+pub fn shares_bought(env: &Env, market_id: u64, user: &Address, outcome: u32, shares: i128, cost: i128) {
+    shares_event(env, SHARES_BOUGHT, market_id, user, outcome, shares, cost);
+}
+
+pub fn shares_sold(env: &Env, market_id: u64, user: &Address, outcome: u32, shares: i128, proceeds: i128) {
+    shares_event(env, SHARES_SOLD, market_id, user, outcome, shares, proceeds);
+}
+
+/// İtiraz açıldı - kayıt olduğu gibi yayınlanır
+pub fn disputed(env: &Env, market_id: u64, dispute: &Dispute) {
+    env.events().publish((DISPUTED, EVENT_VERSION, market_id), dispute.clone());
+}
+
+pub fn dispute_settled(env: &Env, market_id: u64, dispute: &Dispute, winning_outcome: u32) {
+    env.events().publish(
+        (DISPUTE_SETTLED, EVENT_VERSION, market_id),
+        DisputeSettledEvent {
+            challenger: dispute.challenger.clone(),
+            bond: dispute.bond,
+            overturned: dispute.overturned,
+            winning_outcome,
+        },
+    );
+}
+
+pub fn commission(env: &Env, market_id: u64, commission: i128, creator_cut: i128, referral_cut: i128) {
+    env.events().publish(
+        (COMMISSION, EVENT_VERSION, market_id),
+        CommissionEvent {
+            commission,
+            creator_cut,
+            referral_cut,
+            treasury_cut: commission - creator_cut - referral_cut,
+        },
+    );
+}
+
+pub fn round_scheduled(env: &Env, series_id: u64, market_id: u64, start_time: u64) {
+    env.events().publish(
+        (ROUND, EVENT_VERSION, series_id),
+        RoundScheduledEvent { market_id, start_time },
+    );
+}
+
+pub fn role_granted(env: &Env, admin: &Address, account: &Address, role: Role) {
+    env.events().publish(
+        (ROLE_GRANTED, EVENT_VERSION, account.clone()),
+        RoleChangedEvent { role, admin: admin.clone() },
+    );
+}
+
+pub fn role_revoked(env: &Env, admin: &Address, account: &Address, role: Role) {
+    env.events().publish(
+        (ROLE_REVOKED, EVENT_VERSION, account.clone()),
+        RoleChangedEvent { role, admin: admin.clone() },
+    );
+}
+
+pub fn listing_cancelled(env: &Env, market_id: u64, seller: &Address, outcome: u32) {
+    env.events().publish(
+        (UNLISTED, EVENT_VERSION, market_id),
+        ListingCancelledEvent { seller: seller.clone(), outcome },
+    );
+}
+
+pub fn series_stopped(env: &Env, series_id: u64, creator: &Address, round_count: u32) {
+    env.events().publish(
+        (SERIES_STOPPED, EVENT_VERSION, series_id),
+        SeriesStoppedEvent { creator: creator.clone(), round_count },
+    );
+}
+
+pub fn season_started(env: &Env, season: &Season) {
+    env.events().publish((SEASON, EVENT_VERSION, season.id), season.clone());
+}
+
+pub fn admin_transferred(env: &Env, previous_admin: &Address, new_admin: &Address) {
+    env.events().publish(
+        (ADMIN_TRANSFERRED, EVENT_VERSION, new_admin.clone()),
+        AdminTransferredEvent { previous_admin: previous_admin.clone() },
+    );
+}
+
+pub fn config_changed(env: &Env, admin: &Address, change: ConfigChange) {
+    env.events().publish((CONFIG, EVENT_VERSION, admin.clone()), change);
+}
@@ -23,6 +23,10 @@ mod reflector {
         fn last_timestamp(e: Env) -> u64;
         fn version(e: Env) -> u32;
         fn admin(e: Env) -> Option<Address>;
+        fn x_last_price(e: Env, base_asset: Asset, quote_asset: Asset) -> Option<PriceData>;
+        fn x_price(e: Env, base_asset: Asset, quote_asset: Asset, timestamp: u64) -> Option<PriceData>;
+        fn x_prices(e: Env, base_asset: Asset, quote_asset: Asset, records: u32) -> Option<Vec<PriceData>>;
+        fn x_twap(e: Env, base_asset: Asset, quote_asset: Asset, records: u32) -> Option<i128>;
     }
 
     #[contracttype]
@@ -40,17 +44,258 @@ mod reflector {
     }
 }
 
-use reflector::{ReflectorClient, Asset as ReflectorAsset};
+use reflector::{ReflectorClient, PriceData};
+pub use reflector::Asset as ReflectorAsset;
+
+pub mod lmsr;
+pub mod events;
+pub mod payout;
+pub mod volatility;
+#[cfg(feature = "testutils")]
+mod testutils;
 
 #[contract]
 pub struct PredictionMarket;
 
+/// Market'ın izlediği fiyat: tek varlık ya da iki varlığın çapraz fiyatı
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PriceFeed {
+    Single(ReflectorAsset),                // Oracle base'ine göre fiyat (Stellar token, BTC, EUR...)
+    Cross(ReflectorAsset, ReflectorAsset), // base/quote oranı - değişimi base'in quote'a göre performansı
+}
+
+impl PriceFeed {
+    fn last_price(&self, oracle: &ReflectorClient) -> Option<PriceData> {
+        match self {
+            PriceFeed::Single(asset) => oracle.lastprice(asset),
+            PriceFeed::Cross(base, quote) => oracle.x_last_price(base, quote),
+        }
+    }
+
+    fn price_at(&self, oracle: &ReflectorClient, timestamp: u64) -> Option<PriceData> {
+        match self {
+            PriceFeed::Single(asset) => oracle.price(asset, &timestamp),
+            PriceFeed::Cross(base, quote) => oracle.x_price(base, quote, &timestamp),
+        }
+    }
+
+    fn twap(&self, oracle: &ReflectorClient, records: u32) -> Option<i128> {
+        match self {
+            PriceFeed::Single(asset) => oracle.twap(asset, &records),
+            PriceFeed::Cross(base, quote) => oracle.x_twap(base, quote, &records),
+        }
+    }
+
+    fn prices(&self, oracle: &ReflectorClient, records: u32) -> Option<Vec<PriceData>> {
+        match self {
+            PriceFeed::Single(asset) => oracle.prices(asset, &records),
+            PriceFeed::Cross(base, quote) => oracle.x_prices(base, quote, &records),
+        }
+    }
+}
+
+/// Market'ın kapanış fiyatının nasıl belirleneceği
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SettlementMode {
+    Spot,         // end_time'daki son fiyat (lastprice)
+    Twap(u32),    // Reflector `twap` ile son N kayıt
+    Average(u32), // `prices` ile son N kaydın ortalaması (VWAP benzeri)
+}
+
+/// Kazanan outcome'un kapanış fiyatından nasıl belirleneceği.
+/// Tüm çözüm fonksiyonları aynı kuralı `determine_winning_outcome` ile uygular.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutcomeRule {
+    RelativeBand,      // initial_price'a göre % değişim, stable_tolerance bps: UP, DOWN, STABLE
+    AbsoluteBand,      // target_price ± stable_tolerance (mutlak): UP, DOWN, STABLE
+    Strike,            // target_price üstü=UP, eşit veya altı=DOWN
+    VolatilityBand(u32), // RelativeBand; tolerans oluşturulurken son N kaydın volatilitesinden türetilir
+    Buckets(Vec<i128>), // Artan sınırlar; fiyatın düştüğü aralığın index'i kazanır (B0..Bn)
+}
+
+/// Sabit oranlı (bookmaker) market parametreleri
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FixedOddsConfig {
+    pub opening_odds: Vec<i128>, // Outcome başına açılış oranı (1000 = 1.0x)
+    pub max_exposure: i128,      // Outcome başına house'un en fazla net zararı
+}
+
+/// Bahislerin nasıl fiyatlandığı ve ödendiği
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PricingMode {
+    PariMutuel,                // Kazananlar havuzu paylaşır
+    FixedOdds(FixedOddsConfig), // Bahis anındaki oran kilitlenir, ödeme bankroll'dan
+    Lmsr(i128),                 // LMSR AMM, likidite parametresi b; kazanan pay başına 1 birim
+}
+
+/// Admin'in dağıtabildiği yetkiler - admin tüm yetkilere sahiptir
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    MarketCreator, // Market oluşturma
+    Resolver,      // Market çözme, ödeme ve iade
+    Treasurer,     // Hazineden komisyon çekme
+    Arbiter,       // İtirazları karara bağlama
+}
+
+/// Çözüm sonrası itiraz ayarları (admin tarafından)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeConfig {
+    pub window_secs: u64, // 0 = itiraz yok, ödemeler hemen
+    pub bond: i128,       // İtiraz için yatırılacak teminat (betting_token cinsinden)
+}
+
+/// Bir market çözümüne yapılan itiraz - kanıt Reflector'ın end_time'daki fiyat kaydıdır
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub challenger: Address,
+    pub bond: i128,
+    pub evidence_price: i128,  // Reflector `price(asset, end_time)`
+    pub evidence_timestamp: u64, // Kanıt kaydının oracle zamanı
+    pub proposed_outcome: u32, // Kanıt fiyatına göre kazanan outcome
+    pub opened_at: u64,
+    pub is_settled: bool,
+    pub overturned: bool,
+}
+
+/// Market'ın iptal nedeni
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VoidReason {
+    None,               // Market iptal edilmedi
+    OracleOutage,       // Kapanış fiyatı alınamıyor
+    MisspecifiedMarket, // Başlık / parametreler hatalı
+    Manipulation,       // Fiyat veya bahis manipülasyonu
+    Other,
+}
+
+/// Market zamanlaması - `create_market` parametresi
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketSchedule {
+    pub start_time: u64,          // 0 = hemen; ileri bir zamansa market Scheduled olarak bekler
+    pub duration_hours: u64,      // start_time -> end_time
+    pub betting_cutoff_secs: u64, // Bahisler end_time'dan bu kadar önce kapanır
+    pub auto_restart: bool,       // Çözülünce aynı süreyle yeni market aç (seri turlarında yok sayılır)
+    pub series_id: Option<u64>,   // Turun serisi - yalnızca seri turlarında dolu, dışarıdan None
+}
+
+/// Market oluştururken belirlenen bahis limitleri - 0 = limit yok
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BetLimits {
+    pub min_bet: i128,
+    pub max_bet: i128,              // Tek bahiste
+    pub max_user_total: i128,       // Adres başına market'taki toplam stake
+    pub max_side_share_bps: i128,   // Adresin bir outcome havuzunda tutabileceği en büyük pay
+    pub imbalance_limit_bps: i128,  // Outcome hacmin bu payını aşarsa o outcome'a bahis durur
+    pub share_checks_from: i128,    // Pay kontrolleri ilgili havuz bu büyüklüğe ulaşınca devreye girer
+}
+
+/// Sabit aralıklarla tur açan market serisi - her tur şablondan oluşturulan ayrı bir market.
+/// `target_price` 0 ise turların hedef fiyatı, turun açılış fiyatıdır.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketSeries {
+    pub id: u64,
+    pub creator: Address,
+    pub title: String,
+    pub feed: PriceFeed,
+    pub betting_token: Address,
+    pub target_price: i128,
+    pub stable_tolerance: i128,
+    pub duration_hours: u64,
+    pub betting_cutoff_secs: u64,
+    pub cadence_secs: u64, // Ardışık turların start_time farkı
+    pub limits: BetLimits,
+    pub house_edge: i128,
+    pub settlement_mode: SettlementMode,
+    pub outcome_rule: OutcomeRule,
+    pub pricing: PricingMode,
+    pub next_start: u64, // Sıradaki turun start_time'ı
+    pub is_active: bool,
+    pub round_count: u32,
+    pub latest_round: u64,   // En son açılan tur - henüz Scheduled olabilir (0 = yok)
+    pub previous_round: u64, // Ondan önceki tur - en fazla bir tur önceden planlandığından güncel tur bu ikisinden biri
+}
+
+/// `create_series` parametresi - turlar bu şablondan açılır
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum PredictionType {
-    Up,      
-    Down,    
-    Stable,  
+pub struct SeriesTemplate {
+    pub title: String,
+    pub feed: PriceFeed,
+    pub betting_token: Address,
+    pub schedule: MarketSchedule, // İlk turun zamanlaması; süre ve cutoff tüm turlara uygulanır
+    pub cadence_secs: u64,
+    pub config: MarketConfig,
+}
+
+/// Market yaşam döngüsü. Scheduled -> Open -> Closed ve Resolving -> Resolved geçişleri zamanla
+/// kendiliğinden olur (bkz. `Market::status_at`); diğerleri kontrat çağrılarıyla.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MarketStatus {
+    Scheduled, // start_time gelmedi
+    Open,      // Bahis alınıyor
+    Closed,    // Bahisler kapandı (close_time), çözüm bekleniyor
+    Resolving, // Sonuç ilan edildi, itiraz süresi işliyor
+    Disputed,  // Açık itiraz arbiter kararını bekliyor
+    Resolved,  // Sonuç kesinleşti, ödemeler yapılabilir
+    Settled,   // Toplu ödeme / iade tamamlandı
+    Voided,    // İptal edildi, stake'ler iade edilir
+}
+
+impl MarketStatus {
+    /// Geçerli açık geçişler (zamanla olanlar dahil)
+    pub fn can_transition_to(self, next: MarketStatus) -> bool {
+        use MarketStatus::*;
+        matches!(
+            (self, next),
+            (Scheduled, Open)
+                | (Scheduled, Voided)
+                | (Open, Closed)
+                | (Open, Resolving) // Resolver end_time'dan önce de çözebilir
+                | (Open, Voided)
+                | (Closed, Resolving)
+                | (Closed, Voided)
+                | (Resolving, Disputed)
+                | (Resolving, Resolved)
+                | (Disputed, Resolved)
+                | (Resolved, Settled)
+        )
+    }
+}
+
+/// Market'taki tek bir outcome ve ona yatırılan bahisler
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutcomePool {
+    pub label: Symbol,
+    pub total_bets: i128,
+    pub betters_count: u32,
+    pub liability: i128, // Bu outcome kazanırsa ödenecek toplam (sabit oran) / dolaşımdaki pay (LMSR)
+}
+
+/// `create_market`'in market kuralları - kontrat fonksiyonları en fazla 10 parametre alır
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketConfig {
+    pub target_price: i128,     // 0 = açılış fiyatı
+    pub stable_tolerance: i128, // Outcome kuralına göre bps ya da mutlak fiyat
+    pub house_edge: i128,       // Basis points (50 = %0.5)
+    pub settlement_mode: SettlementMode,
+    pub outcome_rule: OutcomeRule,
+    pub pricing: PricingMode,
+    pub limits: BetLimits,
+    pub liquidity: Vec<i128>, // Outcome başına tohum (yalnız pari-mutuel) - boş = tohumsuz
 }
 
 #[contracttype]
@@ -58,47 +303,168 @@ pub enum PredictionType {
 pub struct Market {
     pub id: u64,
     pub title: String,
-    pub token: Address,
+    pub feed: PriceFeed,        // Tahmin edilen fiyat
     pub betting_token: Address, // Bahis yapılan token (XLM, USDC vs)
     pub initial_price: i128,
     pub target_price: i128,
     pub stable_tolerance: i128,
     pub start_time: u64,
     pub end_time: u64,
-    pub total_up_bets: i128,
-    pub total_down_bets: i128,
-    pub total_stable_bets: i128,
-    pub up_betters_count: u32,
-    pub down_betters_count: u32,
-    pub stable_betters_count: u32,
-    pub is_resolved: bool,
-    pub is_paid_out: bool, // Ödemeler yapılmış mı?
-    pub winning_side: Option<u32>, // 0=Up, 1=Down, 2=Stable
+    pub close_time: u64, // Son bahis anı - end_time'dan önce
+    pub outcomes: Vec<OutcomePool>, // index = outcome
+    pub status: MarketStatus, // Son kaydedilen durum - güncel durum için `status_at`
+    pub winning_outcome: Option<u32>, // outcomes içindeki index
     pub final_price: Option<i128>,
     pub auto_restart: bool,
     pub restart_duration: u64,
+    pub limits: BetLimits,
     pub house_edge: i128, // Basis points (50 = %0.5)
+    pub settlement_mode: SettlementMode,
+    pub outcome_rule: OutcomeRule,
+    pub cash_out_surplus: i128, // Cash-out'lardan havuzda kalan miktar (kazananlara dağıtılır)
+    pub creator: Address,
+    pub pricing: PricingMode,
+    pub bankroll: i128, // Sabit oranlı / LMSR marketlerde creator'ın yatırdığı net sermaye
+    pub dispute_deadline: u64, // Bu zamana kadar itiraz edilebilir, ödemeler bekler
+    pub void_reason: VoidReason, // None değilse market iptal edildi, stake'ler iade edilir
+    pub series_id: Option<u64>, // Seri turuysa ait olduğu seri
+    pub liquidity: Vec<i128>,   // Pari-mutuel markette creator'ın outcome başına tohum likiditesi (havuzlara dahil)
+    pub liquidity_returned: bool,
+}
+
+impl Market {
+    pub fn liquidity_total(&self) -> i128 {
+        let mut total = 0i128;
+        for amount in self.liquidity.iter() {
+            total += amount;
+        }
+        total
+    }
+
+    /// Tüm outcome'lara yatırılan toplam miktar
+    pub fn total_volume(&self) -> i128 {
+        let mut total = 0i128;
+        for outcome in self.outcomes.iter() {
+            total += outcome.total_bets;
+        }
+        total
+    }
+
+    pub fn total_betters(&self) -> u32 {
+        let mut total = 0u32;
+        for outcome in self.outcomes.iter() {
+            total += outcome.betters_count;
+        }
+        total
+    }
+
+    /// Outcome'a yatırılan toplam bahis (geçersiz index için 0)
+    pub fn outcome_total(&self, outcome: u32) -> i128 {
+        self.outcomes.get(outcome).map(|o| o.total_bets).unwrap_or(0)
+    }
+
+    pub fn outcome_betters(&self, outcome: u32) -> u32 {
+        self.outcomes.get(outcome).map(|o| o.betters_count).unwrap_or(0)
+    }
+
+    pub fn outcome_liability(&self, outcome: u32) -> i128 {
+        self.outcomes.get(outcome).map(|o| o.liability).unwrap_or(0)
+    }
+
+    pub fn is_pari_mutuel(&self) -> bool {
+        self.pricing == PricingMode::PariMutuel
+    }
+
+    /// Zamanla gerçekleşen geçişler uygulanmış güncel durum
+    pub fn status_at(&self, now: u64) -> MarketStatus {
+        match self.status {
+            MarketStatus::Scheduled | MarketStatus::Open if now >= self.close_time => MarketStatus::Closed,
+            MarketStatus::Scheduled if now >= self.start_time => MarketStatus::Open,
+            MarketStatus::Resolving if now >= self.dispute_deadline => MarketStatus::Resolved,
+            status => status,
+        }
+    }
+
+    /// Durumu `next`'e taşı; geçersiz geçişte neden geçilemediğini söyleyen hata döner
+    pub fn transition(&mut self, now: u64, next: MarketStatus) -> Result<(), Error> {
+        use MarketStatus::*;
+        let current = self.status_at(now);
+        if !current.can_transition_to(next) {
+            return Err(match (current, next) {
+                (Voided, _) => Error::MarketVoided,
+                (Settled, _) => Error::MarketAlreadyPaidOut,
+                (Resolving, Settled) => Error::DisputeWindowOpen,
+                (Disputed, Settled) => Error::MarketDisputed,
+                (Resolved, Disputed) => Error::DisputeWindowClosed,
+                (Resolving | Disputed | Resolved, _) => Error::MarketAlreadyResolved,
+                (_, Disputed | Resolved | Settled) => Error::MarketNotResolved,
+                _ => Error::InvalidStateTransition,
+            });
+        }
+        self.status = next;
+        Ok(())
+    }
+
+    /// Sonuç ilan edilmiş mi (itiraz süresi bitmemiş olsa bile)
+    pub fn is_resolved(&self) -> bool {
+        matches!(
+            self.status,
+            MarketStatus::Resolving | MarketStatus::Disputed | MarketStatus::Resolved | MarketStatus::Settled
+        )
+    }
+
+    pub fn is_paid_out(&self) -> bool {
+        self.status == MarketStatus::Settled
+    }
+
+    pub fn is_voided(&self) -> bool {
+        self.status == MarketStatus::Voided
+    }
 }
 
+/// Pozisyona eklenen tek bir bahis
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct UserBet {
-    pub user: Address,
-    pub market_id: u64,
+pub struct BetEntry {
     pub amount: i128,
-    pub prediction: u32, // 0=Up, 1=Down, 2=Stable
     pub timestamp: u64,
     pub odds_when_placed: i128,
+}
+
+/// Kullanıcının bir market'taki tek outcome üzerindeki toplam pozisyonu
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserBet {
+    pub user: Address,
+    pub market_id: u64,
+    pub amount: i128, // Tüm entry'lerin toplamı
+    pub outcome: u32, // Market.outcomes içindeki index
+    pub entries: Vec<BetEntry>,
     pub is_paid_out: bool, // Ödeme yapılmış mı?
     pub winnings: i128,    // Kazanılan miktar
+    pub shares: i128,      // LMSR marketlerinde sahip olunan pay
+}
+
+/// İkincil piyasada sabit fiyatla satışa çıkarılmış pozisyon
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionListing {
+    pub seller: Address,
+    pub market_id: u64,
+    pub outcome: u32,
+    pub price: i128, // betting_token cinsinden, pozisyonun tamamı için
 }
 
+/// Fiyat geçmişine göre önerilen market parametreleri (bkz. `volatility`)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Odds {
-    pub up_odds: i128,      
-    pub down_odds: i128,
-    pub stable_odds: i128,
+pub struct MarketSuggestion {
+    pub samples: u32,             // Kullanılan oracle kaydı
+    pub period_secs: u64,         // Kayıtlar arası ortalama süre
+    pub volatility_bps: i128,     // Kayıt başına gerçekleşmiş volatilite
+    pub duration_hours: u64,      // İstenen ya da önerilen süre
+    pub expected_move_bps: i128,  // Süre boyunca beklenen (1σ) fiyat hareketi
+    pub stable_tolerance: i128,   // UP / DOWN / STABLE'ı dengeleyen tolerans (bps)
 }
 
 #[contracttype]
@@ -106,20 +472,151 @@ pub struct Odds {
 pub struct MarketStats {
     pub total_volume: i128,
     pub total_betters: u32,
-    pub up_percentage: i128,
-    pub down_percentage: i128,
-    pub stable_percentage: i128,
+    pub outcome_percentages: Vec<i128>, // Basis points, outcome index sırasıyla
     pub prize_pool: i128, // Komisyon sonrası ödül havuzu
     pub house_commission: i128, // Kesilen komisyon
 }
 
+/// `get_markets` filtresi - durum, market'ın güncel (`status_at`) durumudur
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MarketFilter {
+    All,
+    Status(MarketStatus),
+    Token(Address),   // betting_token
+    Creator(Address),
+    Series(u64),
+}
+
+/// Sayfalı market listesi - `next_cursor` sonraki çağrının cursor'ı, None ise son sayfa
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketPage {
+    pub markets: Vec<Market>,
+    pub next_cursor: Option<u64>,
+}
+
+/// Sayfalı pozisyon listesi (sayfa başına `limit` kullanıcının tüm pozisyonları)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BetPage {
+    pub bets: Vec<UserBet>,
+    pub next_cursor: Option<u32>,
+}
+
+/// Kullanıcının bir market'taki geçmişi
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserMarketHistory {
+    pub market_id: u64,
+    pub status: MarketStatus,
+    pub winning_outcome: Option<u32>,
+    pub positions: Vec<UserBet>,     // Güncel pozisyonlar (cash-out / satılanlar hariç)
+    pub staked: i128,                // Bahis, pay ve pozisyon alımlarına yatırılan toplam
+    pub returned: i128,              // Kazanç, iade, cash-out ve satışlardan alınan toplam
+    pub realized_pnl: Option<i128>,  // returned - staked; açık pozisyon kalmadıysa
+}
+
+/// Sayfalı kullanıcı geçmişi - en yeni market önce
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserHistoryPage {
+    pub records: Vec<UserMarketHistory>,
+    pub next_cursor: Option<u32>,
+}
+
+/// Market'taki toplam stake'iyle bir kullanıcı
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BetterStake {
+    pub user: Address,
+    pub staked: i128,
+}
+
+/// Bir kullanıcı aralığındaki en büyük stake'ler
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BetterPage {
+    pub betters: Vec<BetterStake>,
+    pub next_cursor: Option<u32>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PayoutSummary {
     pub total_winners: u32,
     pub total_paid_amount: i128,
     pub house_commission: i128,
-    pub remaining_balance: i128,
+    pub remaining_balance: i128, // Ödül havuzunda kalan: yuvarlama artığı (dust) ve başarısız transferler
+}
+
+/// Referans veren bir adresin tek token'daki referans kaydı - ödül defteri de budur
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralStats {
+    pub referred_bets: u32,
+    pub referred_volume: i128,
+    pub rewards_earned: i128,  // Çözülen marketlerde kazanılan komisyon payı
+    pub rewards_claimed: i128, // Çekilebilir ödül = earned - claimed
+}
+
+/// Kullanıcının tek token'daki sonuçlanmış marketleri - hem ömür boyu hem sezon kaydı
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserStats {
+    pub markets_played: u32,
+    pub wins: u32,      // Kazanan outcome'da pozisyonu olan marketler
+    pub volume: i128,   // Yatırılan toplam (bahis + pozisyon alımı)
+    pub net_pnl: i128,  // Alınan - yatırılan
+    pub points: u32,    // Sadakat puanı: market başına PLAY_POINTS, kazançta ek WIN_POINTS
+}
+
+impl UserStats {
+    fn empty() -> Self {
+        UserStats { markets_played: 0, wins: 0, volume: 0, net_pnl: 0, points: 0 }
+    }
+
+    /// `new_market` false ise aynı marketin önceki kaydına eklenen akıştır (çıkıp yeniden giren kullanıcı)
+    fn record(&mut self, staked: i128, returned: i128, won: bool, new_market: bool) {
+        if new_market {
+            self.markets_played += 1;
+            self.points += PLAY_POINTS;
+        }
+        self.volume += staked;
+        self.net_pnl += returned - staked;
+        if won {
+            self.wins += 1;
+            self.points += WIN_POINTS;
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardEntry {
+    pub user: Address,
+    pub stats: UserStats,
+}
+
+/// Liderlik tablosu sezonu - admin yeni sezonu başlatana kadar sürer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Season {
+    pub id: u32,
+    pub started_at: u64,
+}
+
+/// Bir token için hazine ile kontrat bakiyesinin mutabakatı
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasuryReport {
+    pub token: Address,
+    pub accrued_commission: i128, // Admin'in çekebileceği birikmiş komisyon
+    pub creator_fees: i128,       // Creator'ların henüz çekmediği komisyon payları
+    pub referral_rewards: i128,   // Referans verenlerin henüz çekmediği ödüller
+    pub locked_in_markets: i128,  // Açık marketlerdeki stake'ler, ödenmemiş kazançlar ve bankroll'lar
+    pub contract_balance: i128,
+    pub unallocated: i128,        // Kalan: yuvarlama artığı, başarısız transferler vb.
 }
 
 #[contracterror]
@@ -140,12 +637,79 @@ pub enum Error {
     PaymentFailed = 13,
     TokenTransferFailed = 14,
     CalculationError = 15,
+    OraclePriceUnavailable = 16,
+    InvalidSettlementMode = 17,
+    InvalidOutcomeRule = 18,
+    PositionNotFound = 19,
+    CashOutWindowClosed = 20,
+    ListingNotFound = 21,
+    InvalidTransfer = 22,
+    InvalidHouseEdge = 23,
+    InvalidPricingMode = 24,
+    InsufficientBankroll = 25,
+    ExposureLimitExceeded = 26,
+    UnsupportedPricingMode = 27,
+    SlippageExceeded = 28,
+    DisputeWindowOpen = 30,
+    DisputeWindowClosed = 31,
+    MarketDisputed = 32,
+    InvalidDispute = 33,
+    DisputeNotFound = 34,
+    MarketVoided = 35,
+    MarketNotResolved = 36,
+    InvalidStateTransition = 37,
+    InvalidSchedule = 38,
+    SeriesNotFound = 39,
+    RoundNotDue = 40,
+    SeriesStopped = 41,
+    InvalidBetLimits = 42,
+    BetBelowMinimum = 43,
+    BetAboveMaximum = 44,
+    BetLimitExceeded = 45,
+    OutcomeSuspended = 46,
+    InvalidPriceFeed = 47,
+    InsufficientPriceHistory = 48,
+    InvalidReferrer = 49,
+    InvalidVoidReason = 51,
 }
 
-const MARKETS: Symbol = symbol_short!("MARKETS");
+const MARKETS: Symbol = symbol_short!("MARKETS"); // (MARKETS, id) -> Market, her market ayrı kayıtta
 const MARKET_COUNTER: Symbol = symbol_short!("COUNTER");
-const CONTRACT_BALANCE: Symbol = symbol_short!("BALANCE");
-const MARKET_USERS: Symbol = symbol_short!("MUSERS"); // Market kullanıcıları listesi
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const ROLES: Symbol = symbol_short!("ROLE"); // (role, address) -> bool
+const TREASURY: Symbol = symbol_short!("TREASURY"); // Token başına birikmiş komisyon
+const CREATOR_FEES: Symbol = symbol_short!("CRFEES"); // (creator, token) başına komisyon payı
+const CREATOR_FEE_SHARE: Symbol = symbol_short!("CRSHARE"); // Komisyonun creator'a giden payı (bps)
+const REFERRAL_SHARE: Symbol = symbol_short!("REFSHARE"); // Referanslı bahsin komisyonundan referans payı (bps)
+const REFERRALS: Symbol = symbol_short!("REFS"); // Market başına referans -> referanslı stake
+const REFERRAL_STATS: Symbol = symbol_short!("REFSTATS"); // (referans, token) -> ReferralStats
+const REFERRAL_OWED: Symbol = symbol_short!("REFOWED"); // Token başına çekilmemiş referans ödülleri
+const CREATOR_OWED: Symbol = symbol_short!("CROWED"); // Token başına çekilmemiş creator payları
+const LOCKED: Symbol = symbol_short!("LOCKED"); // Token başına marketlerde kilitli tutar
+const USER_STATS: Symbol = symbol_short!("USTATS"); // (kullanıcı, token) -> ömür boyu UserStats
+const SEASON: Symbol = symbol_short!("SEASON"); // Güncel Season
+const SEASON_STATS: Symbol = symbol_short!("SSTATS"); // (sezon, token, kullanıcı) -> UserStats
+const SEASON_TOP: Symbol = symbol_short!("STOP"); // (sezon, token) -> sıralı ilk LEADERBOARD_SIZE kayıt
+const STATS_RECORDED: Symbol = symbol_short!("STATSREC"); // (kullanıcı, market) -> istatistiğe işlenen (yatırılan, alınan)
+const DISPUTE_CONFIG: Symbol = symbol_short!("DSPCFG");
+const DISPUTES: Symbol = symbol_short!("DISPUTES"); // Market başına itiraz kaydı
+const MARKET_USERS: Symbol = symbol_short!("MUSERS"); // (market) -> sayı, (market, sıra) -> kullanıcı, (market, kullanıcı) -> sıra
+const USER_MARKETS: Symbol = symbol_short!("UMARKETS"); // Kullanıcı -> pozisyon aldığı market id'leri
+const USER_FLOWS: Symbol = symbol_short!("UFLOWS"); // (kullanıcı, market) -> (yatırılan, alınan)
+const LISTINGS: Symbol = symbol_short!("LISTINGS"); // Market başına satıştaki pozisyonlar
+const SERIES: Symbol = symbol_short!("SERIES"); // series_id -> MarketSeries
+const SERIES_COUNTER: Symbol = symbol_short!("SRCOUNT");
+const SERIES_ROUNDS: Symbol = symbol_short!("SRROUNDS"); // (series_id, sıra) -> turun market id'si
+const CASH_OUT_FEE_BPS: i128 = 200; // %2
+const CASH_OUT_CUTOFF_SECS: u64 = 3600; // Bahisler kapanmadan önceki son 1 saat cash-out yok
+const MAX_OUTCOMES: u32 = 8;
+const MAX_PAGE_SIZE: u32 = 50;
+const LEADERBOARD_SIZE: u32 = MAX_PAGE_SIZE;
+const MAX_PAGE_SCAN: u64 = 200; // get_markets'in bir sayfada okuyacağı en fazla market kaydı
+const MAX_USER_SCAN: u32 = 200; // get_top_betters'ın bir çağrıda okuyacağı en fazla kullanıcı
+const PLAY_POINTS: u32 = 10;
+const WIN_POINTS: u32 = 20;
+const BUCKET_LABELS: [&str; MAX_OUTCOMES as usize] = ["B0", "B1", "B2", "B3", "B4", "B5", "B6", "B7"];
 const ORACLE_ADDRESS_STR: &str = "CAVLP5DH2GJPZMVO7IJY4CVOD5MWEFTJFVPD2YY2FQXOQHRGHK4D6HLP";
 
 #[contractimpl]
@@ -170,351 +734,2137 @@ impl PredictionMarket {
         }
     }
 
-    /// Gelişmiş market oluştur
-    pub fn create_market(
+    /// Feed'in son fiyatı (çapraz feed'de base/quote oranı)
+    pub fn get_feed_price(env: Env, feed: PriceFeed) -> i128 {
+        feed.last_price(&Self::get_oracle_client(&env))
+            .map(|price_data| price_data.price)
+            .unwrap_or(0)
+    }
+
+    /// Feed'in son `records` kaydının volatilitesine göre tolerans ve süre önerisi.
+    /// `duration_hours` 0 ise STABLE bandının anlamlı olduğu en kısa süre önerilir.
+    pub fn suggest_market_params(
         env: Env,
-        admin: Address,
-        title: String,
-        token: Address,
-        betting_token: Address, // Bahis yapılacak token
-        target_price: i128,
-        stable_tolerance: i128,
+        feed: PriceFeed,
+        records: u32,
         duration_hours: u64,
-        auto_restart: bool,
-        house_edge: i128, // Basis points (50 = %0.5)
-    ) -> Result<u64, Error> {
-        admin.require_auth();
+    ) -> Result<MarketSuggestion, Error> {
+        if records < volatility::MIN_RECORDS {
+            return Err(Error::InsufficientPriceHistory);
+        }
+        let prices = feed.prices(&Self::get_oracle_client(&env), records)
+            .ok_or(Error::OraclePriceUnavailable)?;
+        let (variance, period_secs) = volatility::realized_variance(&prices)?;
         
-        let market_id = env.storage().persistent()
-            .get(&MARKET_COUNTER).unwrap_or(0u64) + 1;
+        let duration_hours = if duration_hours == 0 {
+            volatility::suggested_duration_hours(variance, period_secs)?
+        } else {
+            duration_hours
+        };
+        let expected_move_bps = volatility::expected_move_bps(variance, period_secs, duration_hours * 3600)?;
+        
+        Ok(MarketSuggestion {
+            samples: prices.len(),
+            period_secs,
+            volatility_bps: volatility::expected_move_bps(variance, period_secs, period_secs)?,
+            duration_hours,
+            expected_move_bps,
+            stable_tolerance: volatility::balanced_tolerance(expected_move_bps),
+        })
+    }
+
+    /// Açılış fiyatı yoksa Reflector'ın start_time kaydından al (planlı marketler başladıktan
+    /// sonraki ilk işlemde). Oracle verisi yoksa sonraki işlem tekrar dener.
+    fn snapshot_initial_price(env: &Env, market: &mut Market) {
+        if market.initial_price != 0 || env.ledger().timestamp() < market.start_time {
+            return;
+        }
+        let reflector_client = Self::get_oracle_client(env);
+        if let Some(price_data) = market.feed
+            .price_at(&reflector_client, market.start_time)
+            .or_else(|| market.feed.last_price(&reflector_client))
+        {
+            market.initial_price = price_data.price;
+            if market.target_price == 0 {
+                market.target_price = market.initial_price;
+            }
+        }
+    }
+
+    /// Settlement moduna göre oracle'dan kapanış fiyatını al. Zaman damgası kullanılan
+    /// en yeni oracle kaydınındır (TWAP / Average'da pencerenin sonu).
+    fn get_settlement_price(env: &Env, market: &Market) -> Option<PriceData> {
+        let reflector_client = Self::get_oracle_client(env);
+        
+        match market.settlement_mode {
+            SettlementMode::Spot => market.feed.last_price(&reflector_client),
+            SettlementMode::Twap(records) => {
+                let latest = market.feed.last_price(&reflector_client)?;
+                let price = market.feed.twap(&reflector_client, records)?;
+                Some(PriceData { price, timestamp: latest.timestamp })
+            },
+            SettlementMode::Average(records) => {
+                let prices = market.feed.prices(&reflector_client, records)?;
+                if prices.is_empty() {
+                    return None;
+                }
+                
+                let mut sum = 0i128;
+                let mut timestamp = 0u64;
+                for price_data in prices.iter() {
+                    sum += price_data.price;
+                    timestamp = timestamp.max(price_data.timestamp);
+                }
+                Some(PriceData { price: sum / prices.len() as i128, timestamp })
+            },
+        }
+    }
+
+    /// Manuel çözümlerde kullanılacak fiyat: Spot marketlerde admin fiyatı (oracle kaydı
+    /// olmadığından zaman damgası 0), TWAP/Average marketlerde her zaman oracle ortalaması
+    fn get_manual_settlement_price(env: &Env, market: &Market, final_price: i128) -> Result<PriceData, Error> {
+        match market.settlement_mode {
+            SettlementMode::Spot => Ok(PriceData { price: final_price, timestamp: 0 }),
+            _ => Self::get_settlement_price(env, market).ok_or(Error::OraclePriceUnavailable),
+        }
+    }
+
+    /// Kazanan outcome'u market'ın OutcomeRule'una göre belirle - tüm çözüm yolları bunu kullanır
+    fn determine_winning_outcome(market: &Market, final_price: i128) -> Result<u32, Error> {
+        match &market.outcome_rule {
+            OutcomeRule::RelativeBand | OutcomeRule::VolatilityBand(_) => {
+                if market.initial_price == 0 {
+                    return Err(Error::CalculationError);
+                }
+                let price_change_bps = ((final_price - market.initial_price) * 10000) / market.initial_price;
+                
+                if price_change_bps > market.stable_tolerance {
+                    Ok(0) // Up
+                } else if price_change_bps < -market.stable_tolerance {
+                    Ok(1) // Down
+                } else {
+                    Ok(2) // Stable
+                }
+            },
+            OutcomeRule::AbsoluteBand => {
+                if final_price > market.target_price + market.stable_tolerance {
+                    Ok(0) // Up
+                } else if final_price < market.target_price - market.stable_tolerance {
+                    Ok(1) // Down
+                } else {
+                    Ok(2) // Stable
+                }
+            },
+            OutcomeRule::Strike => {
+                if final_price > market.target_price {
+                    Ok(0) // Up
+                } else {
+                    Ok(1) // Down
+                }
+            },
+            OutcomeRule::Buckets(boundaries) => {
+                let mut bucket = 0u32;
+                for boundary in boundaries.iter() {
+                    if final_price < boundary {
+                        break;
+                    }
+                    bucket += 1;
+                }
+                Ok(bucket)
+            },
+        }
+    }
+
+    /// Market'ı çözülmüş olarak işaretle ve komisyonu hazineye aktar - tüm çözüm yolları bunu kullanır.
+    /// `oracle_settled` false ise final_price admin'in verdiği spot fiyattır.
+    fn settle_market(
+        env: &Env,
+        market: &mut Market,
+        winning_outcome: u32,
+        final_price: &PriceData,
+        oracle_settled: bool,
+    ) -> Result<(), Error> {
+        market.transition(env.ledger().timestamp(), MarketStatus::Resolving)?;
+        market.winning_outcome = Some(winning_outcome);
+        market.final_price = Some(final_price.price);
+        market.dispute_deadline = env.ledger().timestamp() + Self::get_dispute_config(env.clone()).window_secs;
+        
+        events::market_closed(env, market);
+        events::market_resolved(env, market, oracle_settled, final_price.timestamp);
+        
+        // Komisyon hazine defterlerine, yuvarlama artığı kimsenin alacağı olmadığından kilitten çıkar
+        let commission = payout::house_commission(market.total_volume(), market.house_edge)?;
+        Self::adjust_locked(env, &market.betting_token, -commission - Self::rounding_dust(env, market)?);
+        if commission == 0 {
+            return Ok(());
+        }
         
-        let start_time = env.ledger().timestamp();
-        let end_time = start_time + (duration_hours * 3600);
-        let initial_price = Self::get_price(env.clone(), token.clone());
+        let share_bps: i128 = env.storage().persistent().get(&CREATOR_FEE_SHARE).unwrap_or(0);
+        let creator_cut = payout::pro_rata(share_bps, payout::BPS_DENOMINATOR, commission)?;
         
-        let market = Market {
-            id: market_id,
-            title,
-            token,
-            betting_token,
-            initial_price,
-            target_price,
-            stable_tolerance,
-            start_time,
-            end_time,
-            total_up_bets: 0,
-            total_down_bets: 0,
-            total_stable_bets: 0,
-            up_betters_count: 0,
-            down_betters_count: 0,
-            stable_betters_count: 0,
-            is_resolved: false,
-            is_paid_out: false,
-            winning_side: None,
-            final_price: None,
-            auto_restart,
-            restart_duration: duration_hours,
-            house_edge,
+        let referral_cut = Self::credit_referrals(env, market, commission - creator_cut)?;
+        
+        let treasury_key = (TREASURY, market.betting_token.clone());
+        let accrued: i128 = env.storage().persistent().get(&treasury_key).unwrap_or(0);
+        env.storage().persistent().set(&treasury_key, &(accrued + commission - creator_cut - referral_cut));
+        
+        if creator_cut > 0 {
+            let creator_key = (CREATOR_FEES, market.creator.clone(), market.betting_token.clone());
+            let owed: i128 = env.storage().persistent().get(&creator_key).unwrap_or(0);
+            env.storage().persistent().set(&creator_key, &(owed + creator_cut));
+            
+            let total_key = (CREATOR_OWED, market.betting_token.clone());
+            let total: i128 = env.storage().persistent().get(&total_key).unwrap_or(0);
+            env.storage().persistent().set(&total_key, &(total + creator_cut));
+        }
+        
+        events::commission(env, market.id, commission, creator_cut, referral_cut);
+        
+        Ok(())
+    }
+
+    /// Market'ın referanslı stake'lerinin komisyonundan referans paylarını ödül defterine yaz.
+    /// Toplam `available`'ı geçmez; referanslı stake cash-out ile çıkmış olsa da house zarar etmez.
+    fn credit_referrals(env: &Env, market: &Market, available: i128) -> Result<i128, Error> {
+        let share_bps: i128 = env.storage().persistent().get(&REFERRAL_SHARE).unwrap_or(0);
+        let referrals: Map<Address, i128> = match env.storage().persistent().get(&(REFERRALS, market.id)) {
+            Some(referrals) if share_bps > 0 => referrals,
+            _ => return Ok(0),
         };
         
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-        markets.set(market_id, market);
+        let mut total = 0i128;
+        for (referrer, referred_volume) in referrals.iter() {
+            let commission = payout::house_commission(referred_volume, market.house_edge)?;
+            let reward = payout::pro_rata(share_bps, payout::BPS_DENOMINATOR, commission)?
+                .min(available - total);
+            if reward <= 0 {
+                continue;
+            }
+            
+            let mut stats = Self::get_referral_stats(env.clone(), referrer.clone(), market.betting_token.clone());
+            stats.rewards_earned += reward;
+            env.storage().persistent().set(&(REFERRAL_STATS, referrer, market.betting_token.clone()), &stats);
+            total += reward;
+        }
+        
+        if total > 0 {
+            let owed_key = (REFERRAL_OWED, market.betting_token.clone());
+            let owed: i128 = env.storage().persistent().get(&owed_key).unwrap_or(0);
+            env.storage().persistent().set(&owed_key, &(owed + total));
+        }
+        Ok(total)
+    }
+
+    /// OutcomeRule parametrelerini kontrol et
+    fn validate_outcome_rule(outcome_rule: &OutcomeRule, stable_tolerance: i128) -> Result<(), Error> {
+        match outcome_rule {
+            OutcomeRule::RelativeBand | OutcomeRule::AbsoluteBand => {
+                if stable_tolerance < 0 {
+                    return Err(Error::InvalidOutcomeRule);
+                }
+            },
+            OutcomeRule::VolatilityBand(records) => {
+                if stable_tolerance < 0 || *records < volatility::MIN_RECORDS {
+                    return Err(Error::InvalidOutcomeRule);
+                }
+            },
+            OutcomeRule::Strike => {},
+            OutcomeRule::Buckets(boundaries) => {
+                if boundaries.is_empty() || boundaries.len() >= MAX_OUTCOMES {
+                    return Err(Error::InvalidOutcomeRule);
+                }
+                for i in 1..boundaries.len() {
+                    if boundaries.get_unchecked(i) <= boundaries.get_unchecked(i - 1) {
+                        return Err(Error::InvalidOutcomeRule);
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// BetLimits parametrelerini kontrol et
+    fn validate_bet_limits(limits: &BetLimits) -> Result<(), Error> {
+        let bps_range = 0..=payout::BPS_DENOMINATOR;
+        if limits.min_bet < 0
+            || limits.max_bet < 0
+            || limits.max_user_total < 0
+            || limits.share_checks_from < 0
+            || !bps_range.contains(&limits.max_side_share_bps)
+            || !bps_range.contains(&limits.imbalance_limit_bps)
+        {
+            return Err(Error::InvalidBetLimits);
+        }
+        if limits.max_bet > 0 && limits.min_bet > limits.max_bet {
+            return Err(Error::InvalidBetLimits);
+        }
+        Ok(())
+    }
+
+    /// PricingMode parametrelerini kontrol et - sabit oranlı marketlerde marj oranlara dahildir
+    fn validate_pricing(pricing: &PricingMode, outcome_count: u32, house_edge: i128) -> Result<(), Error> {
+        match pricing {
+            PricingMode::PariMutuel => {},
+            PricingMode::FixedOdds(config) => {
+                if house_edge != 0 {
+                    return Err(Error::InvalidHouseEdge);
+                }
+                if config.opening_odds.len() != outcome_count || config.max_exposure <= 0 {
+                    return Err(Error::InvalidPricingMode);
+                }
+                for odds in config.opening_odds.iter() {
+                    if odds <= payout::ODDS_DENOMINATOR {
+                        return Err(Error::InvalidPricingMode);
+                    }
+                }
+            },
+            PricingMode::Lmsr(liquidity) => {
+                if house_edge != 0 {
+                    return Err(Error::InvalidHouseEdge);
+                }
+                if *liquidity <= 0 {
+                    return Err(Error::InvalidPricingMode);
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Tohum likiditesini kontrol et ve toplamını döndür - boşsa 0
+    fn validate_liquidity(pricing: &PricingMode, liquidity: &Vec<i128>, outcome_count: u32) -> Result<i128, Error> {
+        if liquidity.is_empty() {
+            return Ok(0);
+        }
+        if !matches!(pricing, PricingMode::PariMutuel) {
+            return Err(Error::UnsupportedPricingMode);
+        }
+        if liquidity.len() != outcome_count {
+            return Err(Error::InvalidPrediction);
+        }
+        
+        let mut total = 0i128;
+        for amount in liquidity.iter() {
+            if amount < 0 {
+                return Err(Error::InvalidAmount);
+            }
+            total = total.checked_add(amount).ok_or(Error::CalculationError)?;
+        }
+        if total == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Ok(total)
+    }
+
+    /// Sabit oranlı marketin güncel oranları: house'un bir outcome'daki net riski arttıkça
+    /// o outcome'un oranı açılış oranından 1.0x'e doğru doğrusal olarak düşer
+    fn fixed_odds_quote(env: &Env, market: &Market, config: &FixedOddsConfig) -> Vec<i128> {
+        let total_volume = market.total_volume();
+        let mut odds = Vec::new(env);
+        for (outcome, opening) in market.outcomes.iter().zip(config.opening_odds.iter()) {
+            let exposure = (outcome.liability - total_volume).clamp(0, config.max_exposure);
+            let margin = opening - payout::ODDS_DENOMINATOR;
+            odds.push_back(payout::ODDS_DENOMINATOR + margin * (config.max_exposure - exposure) / config.max_exposure);
+        }
+        odds
+    }
+
+    /// OutcomeRule'a göre market'ın outcome listesini oluştur
+    fn build_outcomes(env: &Env, outcome_rule: &OutcomeRule) -> Vec<OutcomePool> {
+        let mut labels = Vec::new(env);
+        match outcome_rule {
+            OutcomeRule::RelativeBand | OutcomeRule::AbsoluteBand | OutcomeRule::VolatilityBand(_) => {
+                labels.push_back(symbol_short!("UP"));
+                labels.push_back(symbol_short!("DOWN"));
+                labels.push_back(symbol_short!("STABLE"));
+            },
+            OutcomeRule::Strike => {
+                labels.push_back(symbol_short!("UP"));
+                labels.push_back(symbol_short!("DOWN"));
+            },
+            OutcomeRule::Buckets(boundaries) => {
+                for i in 0..=boundaries.len() {
+                    labels.push_back(Symbol::new(env, BUCKET_LABELS[i as usize]));
+                }
+            },
+        }
+        
+        let mut outcomes = Vec::new(env);
+        for label in labels.iter() {
+            outcomes.push_back(OutcomePool {
+                label,
+                total_bets: 0,
+                betters_count: 0,
+                liability: 0,
+            });
+        }
+        outcomes
+    }
+
+    /// Gelişmiş market oluştur
+    pub fn create_market(
+        env: Env,
+        admin: Address,
+        title: String,
+        feed: PriceFeed,
+        betting_token: Address, // Bahis yapılacak token
+        schedule: MarketSchedule,
+        config: MarketConfig,
+    ) -> Result<u64, Error> {
+        Self::require_role(&env, &admin, Role::MarketCreator)?;
+        
+        // Seri turları yalnızca create_series / start_next_round ile açılır
+        if schedule.series_id.is_some() {
+            return Err(Error::InvalidSchedule);
+        }
+        
+        Self::insert_market(&env, admin, title, feed, betting_token, schedule, config)
+    }
+
+    /// Market'ı doğrula ve kaydet - yetki kontrolü çağıranda
+    fn insert_market(
+        env: &Env,
+        creator: Address,
+        title: String,
+        feed: PriceFeed,
+        betting_token: Address,
+        schedule: MarketSchedule,
+        config: MarketConfig,
+    ) -> Result<u64, Error> {
+        let MarketConfig {
+            target_price, stable_tolerance, house_edge, settlement_mode, outcome_rule, pricing, limits, liquidity,
+        } = config;
+        let series_id = schedule.series_id;
+        
+        Self::validate_outcome_rule(&outcome_rule, stable_tolerance)?;
+        
+        if !(0..=payout::BPS_DENOMINATOR).contains(&house_edge) {
+            return Err(Error::InvalidHouseEdge);
+        }
+        
+        let mut outcomes = Self::build_outcomes(env, &outcome_rule);
+        Self::validate_pricing(&pricing, outcomes.len(), house_edge)?;
+        Self::validate_bet_limits(&limits)?;
+        let seeded = Self::validate_liquidity(&pricing, &liquidity, outcomes.len())?;
+        
+        if let PriceFeed::Cross(base, quote) = &feed {
+            if base == quote {
+                return Err(Error::InvalidPriceFeed);
+            }
+        }
+        
+        match settlement_mode {
+            SettlementMode::Twap(0) | SettlementMode::Average(0) => {
+                return Err(Error::InvalidSettlementMode);
+            },
+            _ => {}
+        }
+        
+        let market_id = env.storage().persistent()
+            .get(&MARKET_COUNTER).unwrap_or(0u64) + 1;
+        
+        let now = env.ledger().timestamp();
+        let start_time = if schedule.start_time == 0 { now } else { schedule.start_time };
+        let duration_secs = schedule.duration_hours * 3600;
+        if start_time < now || schedule.betting_cutoff_secs >= duration_secs {
+            return Err(Error::InvalidSchedule);
+        }
+        let end_time = start_time + duration_secs;
+        
+        // VolatilityBand: verilen tolerans alt sınır, türetilen daha genişse o kullanılır
+        let stable_tolerance = match outcome_rule {
+            OutcomeRule::VolatilityBand(records) => {
+                let suggestion = Self::suggest_market_params(
+                    env.clone(), feed.clone(), records, schedule.duration_hours,
+                )?;
+                stable_tolerance.max(suggestion.stable_tolerance)
+            },
+            _ => stable_tolerance,
+        };
+        
+        // Planlı marketin açılış fiyatı start_time'dan sonraki ilk işlemde alınır
+        let (status, initial_price) = if start_time > now {
+            (MarketStatus::Scheduled, 0)
+        } else {
+            (MarketStatus::Open, Self::get_feed_price(env.clone(), feed.clone()))
+        };
+        // target_price 0 = hedef açılış fiyatı (seri turları)
+        let target_price = if target_price == 0 { initial_price } else { target_price };
+        
+        // Tohum oluşturma anında yatırılır, ilk bahisten önce havuzlarda olması garanti
+        if seeded > 0 {
+            let token_client = token::Client::new(env, &betting_token);
+            token_client.transfer(&creator, &env.current_contract_address(), &seeded);
+            Self::adjust_locked(env, &betting_token, seeded);
+            
+            for (i, amount) in liquidity.iter().enumerate() {
+                let mut pool = outcomes.get_unchecked(i as u32);
+                pool.total_bets += amount;
+                outcomes.set(i as u32, pool);
+            }
+        }
+        
+        let market = Market {
+            id: market_id,
+            title,
+            feed,
+            betting_token,
+            initial_price,
+            target_price,
+            stable_tolerance,
+            start_time,
+            end_time,
+            close_time: end_time - schedule.betting_cutoff_secs,
+            outcomes,
+            status,
+            winning_outcome: None,
+            final_price: None,
+            auto_restart: schedule.auto_restart,
+            restart_duration: schedule.duration_hours,
+            limits,
+            house_edge,
+            settlement_mode,
+            outcome_rule,
+            cash_out_surplus: 0,
+            creator,
+            pricing,
+            bankroll: 0,
+            dispute_deadline: 0,
+            void_reason: VoidReason::None,
+            series_id,
+            liquidity,
+            liquidity_returned: false,
+        };
+        
+        events::market_created(env, &market);
+        if seeded > 0 {
+            events::liquidity_added(env, &market);
+        }
+        
+        Self::save_market(env, &market);
+        env.storage().persistent().set(&MARKET_COUNTER, &market_id);
+        
+        Ok(market_id)
+    }
+
+    /// Çözülen market'ın devamını aç - market kaydedildikten sonra tüm çözüm yolları çağırır.
+    /// Seri turuysa sıradaki tur, auto_restart ise aynı parametrelerle yeni market açılır.
+    fn continue_after_resolution(env: &Env, market: &Market, final_price: i128) {
+        if let Some(series_id) = market.series_id {
+            // Tur açılamazsa (erken, durdurulmuş seri) çözüm yine geçerli; start_next_round ile açılır
+            let _ = Self::open_next_round(env, series_id);
+            return;
+        }
+        
+        // Otomatik yeniden başlatma - yeni market de orijinal creator'a ait.
+        // Açılamazsa (ör. oracle geçmişi yetersiz) çözüm geri alınmaz, market yeniden başlamaz.
+        if market.auto_restart {
+            let _ = Self::insert_market(
+                env,
+                market.creator.clone(),
+                market.title.clone(),
+                market.feed.clone(),
+                market.betting_token.clone(),
+                MarketSchedule {
+                    start_time: 0,
+                    duration_hours: market.restart_duration,
+                    betting_cutoff_secs: market.end_time - market.close_time,
+                    auto_restart: true,
+                    series_id: None,
+                },
+                MarketConfig {
+                    target_price: final_price,
+                    stable_tolerance: market.stable_tolerance,
+                    house_edge: market.house_edge,
+                    settlement_mode: market.settlement_mode.clone(),
+                    outcome_rule: market.outcome_rule.clone(),
+                    pricing: market.pricing.clone(),
+                    limits: market.limits.clone(),
+                    liquidity: Vec::new(env), // Yeniden başlatmada creator'ın imzası yok - tohumsuz açılır
+                },
+            );
+        }
+    }
+
+    /// Tekrarlayan market serisi oluştur - ilk tur hemen (ya da `schedule.start_time`'da) açılır
+    pub fn create_series(
+        env: Env,
+        creator: Address,
+        template: SeriesTemplate,
+    ) -> Result<u64, Error> {
+        Self::require_role(&env, &creator, Role::MarketCreator)?;
+        
+        let SeriesTemplate {
+            title, feed, betting_token, schedule, cadence_secs, config,
+        } = template;
+        let MarketConfig {
+            target_price, stable_tolerance, house_edge, settlement_mode, outcome_rule, pricing, limits, liquidity,
+        } = config;
+        
+        if cadence_secs == 0 || schedule.series_id.is_some() {
+            return Err(Error::InvalidSchedule);
+        }
+        
+        // Turları herkes açabilir; creator'ın imzası olmadan her tura tohum çekilemez
+        if !liquidity.is_empty() {
+            return Err(Error::InvalidAmount);
+        }
+        
+        let series_id = env.storage().persistent()
+            .get(&SERIES_COUNTER).unwrap_or(0u64) + 1;
+        
+        let now = env.ledger().timestamp();
+        let series = MarketSeries {
+            id: series_id,
+            creator,
+            title,
+            feed,
+            betting_token,
+            target_price,
+            stable_tolerance,
+            duration_hours: schedule.duration_hours,
+            betting_cutoff_secs: schedule.betting_cutoff_secs,
+            cadence_secs,
+            limits,
+            house_edge,
+            settlement_mode,
+            outcome_rule,
+            pricing,
+            next_start: if schedule.start_time == 0 { now } else { schedule.start_time },
+            is_active: true,
+            round_count: 0,
+            latest_round: 0,
+            previous_round: 0,
+        };
+        
+        // İlk tur şablonu doğrular: geçersizse seri de oluşmaz
+        Self::open_round(&env, series)?;
+        env.storage().persistent().set(&SERIES_COUNTER, &series_id);
+        
+        Ok(series_id)
+    }
+
+    /// Serinin sıradaki turunu aç - herkes çağırabilir, önceki tur başladıktan sonra
+    pub fn start_next_round(env: Env, series_id: u64) -> Result<u64, Error> {
+        Self::open_next_round(&env, series_id)
+    }
+
+    /// Seriyi durdur - açık turlar normal şekilde çözülür, yeni tur açılmaz
+    pub fn stop_series(env: Env, creator: Address, series_id: u64) -> Result<(), Error> {
+        creator.require_auth();
+        
+        let mut series = Self::get_series(env.clone(), series_id).ok_or(Error::SeriesNotFound)?;
+        if creator != series.creator {
+            return Err(Error::NotAuthorized);
+        }
+        
+        series.is_active = false;
+        env.storage().persistent().set(&(SERIES, series_id), &series);
+        
+        events::series_stopped(&env, series_id, &creator, series.round_count);
+        Ok(())
+    }
+
+    pub fn get_series(env: Env, series_id: u64) -> Option<MarketSeries> {
+        env.storage().persistent().get(&(SERIES, series_id))
+    }
+
+    /// Serinin turları eskiden yeniye, tur sırası üzerinden sayfalı
+    pub fn get_series_rounds(env: Env, series_id: u64, cursor: u64, limit: u32) -> MarketPage {
+        let round_count = Self::get_series(env.clone(), series_id).map_or(0, |series| series.round_count) as u64;
+        let end = cursor.saturating_add(limit.clamp(1, MAX_PAGE_SIZE) as u64).min(round_count);
+        
+        let mut rounds = Vec::new(&env);
+        for index in cursor..end {
+            let market_id: Option<u64> = env.storage().persistent().get(&(SERIES_ROUNDS, series_id, index as u32));
+            if let Some(market) = market_id.and_then(|market_id| Self::get_market(env.clone(), market_id)) {
+                rounds.push_back(market);
+            }
+        }
+        MarketPage {
+            markets: rounds,
+            next_cursor: if end < round_count { Some(end) } else { None },
+        }
+    }
+
+    /// Başlamış en son tur (sıradaki tur henüz Scheduled olabilir)
+    pub fn get_current_round(env: Env, series_id: u64) -> Option<Market> {
+        let series = Self::get_series(env.clone(), series_id)?;
+        let now = env.ledger().timestamp();
+        [series.latest_round, series.previous_round]
+            .into_iter()
+            .filter_map(|market_id| Self::get_market(env.clone(), market_id))
+            .find(|market| market.start_time <= now)
+    }
+
+    fn open_next_round(env: &Env, series_id: u64) -> Result<u64, Error> {
+        let series = Self::get_series(env.clone(), series_id).ok_or(Error::SeriesNotFound)?;
+        
+        if !series.is_active {
+            return Err(Error::SeriesStopped);
+        }
+        
+        // En fazla bir tur önceden planlanır: önceki turun başlamış olması gerekir
+        if env.ledger().timestamp() + series.cadence_secs < series.next_start {
+            return Err(Error::RoundNotDue);
+        }
+        
+        Self::open_round(env, series)
+    }
+
+    /// Şablondan yeni tur oluştur ve seriyi kaydet
+    fn open_round(env: &Env, mut series: MarketSeries) -> Result<u64, Error> {
+        // Kaçırılan turlar atlanır: start_time her zaman cadence hizasında kalır
+        let now = env.ledger().timestamp();
+        let mut start_time = series.next_start;
+        if start_time < now {
+            start_time += (now - start_time).div_ceil(series.cadence_secs) * series.cadence_secs;
+        }
+        
+        let market_id = Self::insert_market(
+            env,
+            series.creator.clone(),
+            series.title.clone(),
+            series.feed.clone(),
+            series.betting_token.clone(),
+            MarketSchedule {
+                start_time,
+                duration_hours: series.duration_hours,
+                betting_cutoff_secs: series.betting_cutoff_secs,
+                auto_restart: false,
+                series_id: Some(series.id),
+            },
+            MarketConfig {
+                target_price: series.target_price,
+                stable_tolerance: series.stable_tolerance,
+                house_edge: series.house_edge,
+                settlement_mode: series.settlement_mode.clone(),
+                outcome_rule: series.outcome_rule.clone(),
+                pricing: series.pricing.clone(),
+                limits: series.limits.clone(),
+                liquidity: Vec::new(env),
+            },
+        )?;
+        
+        env.storage().persistent().set(&(SERIES_ROUNDS, series.id, series.round_count), &market_id);
+        series.round_count += 1;
+        series.previous_round = series.latest_round;
+        series.latest_round = market_id;
+        
+        series.next_start = start_time + series.cadence_secs;
+        env.storage().persistent().set(&(SERIES, series.id), &series);
+        
+        events::round_scheduled(env, series.id, market_id, start_time);
+        
+        Ok(market_id)
+    }
+
+    /// ADİL ÖDÜL SİSTEMİ - Pool-Based Fair Odds Calculation
+    /// Bu sistem odds'ları pool'daki gerçek para miktarına göre hesaplar
+    pub fn calculate_improved_odds(env: Env, market_id: u64) -> Result<Vec<i128>, Error> {
+        let market = Self::load_market(&env, market_id)?;
+        
+        // Sabit oranlı marketlerde oranlar pool'a değil house'un riskine göre belirlenir
+        if let PricingMode::FixedOdds(config) = &market.pricing {
+            return Ok(Self::fixed_odds_quote(&env, &market, config));
+        }
+        
+        // LMSR'de oran doğrudan AMM fiyatının tersidir: 1 / p
+        if let PricingMode::Lmsr(liquidity) = market.pricing {
+            let mut odds = Vec::new(&env);
+            for outcome in 0..market.outcomes.len() {
+                let price = lmsr::price_bps(&market, liquidity, outcome)?.max(1);
+                odds.push_back(payout::ODDS_DENOMINATOR * payout::BPS_DENOMINATOR / price);
+            }
+            return Ok(odds);
+        }
+        
+        // Komisyon sonrası ödül havuzu
+        let prize_pool = payout::prize_pool(&market)?;
+        
+        // **YENİ ADİL SİSTEM**: Her outcome için gerçekçi odds hesapla
+        // Formül: Odds = (Ödül Havuzu / Bahis Miktarı) ama maksimum limitle
+        let max_realistic_odds = 5000i128; // Maksimum 5.0x
+        let min_odds = 1100i128; // Minimum 1.1x (garantili kar)
+        
+        let mut odds = Vec::new(&env);
+        for outcome in market.outcomes.iter() {
+            if outcome.total_bets > 0 {
+                // Pool'daki para / outcome bahisleri = gerçek payout oranı
+                let raw_odds = (prize_pool * 1000) / outcome.total_bets;
+                odds.push_back(raw_odds.max(min_odds).min(max_realistic_odds));
+            } else {
+                odds.push_back(2000); // Default 2.0x (hiç bahis yoksa)
+            }
+        }
+        
+        Ok(odds)
+    }
+
+    /// Bahis koy - Token transferi ile
+    pub fn place_bet(
+        env: Env,
+        user: Address,
+        market_id: u64,
+        amount: i128,
+        outcome: u32, // Market.outcomes içindeki index
+    ) -> Result<(), Error> {
+        user.require_auth();
+        Self::record_bet(&env, &user, market_id, amount, outcome, true)
+    }
+
+    /// Referanslı bahis - çözümde bu bahsin komisyonunun REFERRAL_SHARE kadarı referansa yazılır
+    pub fn place_bet_with_referrer(
+        env: Env,
+        user: Address,
+        market_id: u64,
+        amount: i128,
+        outcome: u32,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        user.require_auth();
+        
+        if referrer.as_ref() == Some(&user) {
+            return Err(Error::InvalidReferrer);
+        }
+        Self::record_bet(&env, &user, market_id, amount, outcome, true)?;
+        
+        let referrer = match referrer {
+            Some(referrer) => referrer,
+            None => return Ok(()),
+        };
+        
+        let referrals_key = (REFERRALS, market_id);
+        let mut referrals: Map<Address, i128> = env.storage().persistent()
+            .get(&referrals_key).unwrap_or(Map::new(&env));
+        referrals.set(referrer.clone(), referrals.get(referrer.clone()).unwrap_or(0) + amount);
+        env.storage().persistent().set(&referrals_key, &referrals);
+        
+        let betting_token = Self::get_market(env.clone(), market_id)
+            .ok_or(Error::MarketNotFound)?
+            .betting_token;
+        let mut stats = Self::get_referral_stats(env.clone(), referrer.clone(), betting_token.clone());
+        stats.referred_bets += 1;
+        stats.referred_volume += amount;
+        env.storage().persistent().set(&(REFERRAL_STATS, referrer.clone(), betting_token), &stats);
+        
+        events::referred_bet(&env, market_id, &user, &referrer, amount);
+        Ok(())
+    }
+
+    /// Kullanıcının bir outcome'daki pozisyonunun storage anahtarı
+    fn user_bet_key(market_id: u64, user: &Address, outcome: u32) -> (Symbol, u64, Address, u32) {
+        (symbol_short!("UBET"), market_id, user.clone(), outcome)
+    }
+
+    /// Market'ta pozisyonu olan kullanıcı sayısı - liste sıra başına ayrı kayıtta tutulur,
+    /// sayfalı sorgular yalnızca istenen sıraları okur
+    fn market_user_count(env: &Env, market_id: u64) -> u32 {
+        env.storage().persistent().get(&(MARKET_USERS, market_id)).unwrap_or(0)
+    }
+
+    fn market_user_at(env: &Env, market_id: u64, index: u32) -> Option<Address> {
+        env.storage().persistent().get(&(MARKET_USERS, market_id, index))
+    }
+
+    /// Market'ta pozisyonu olan tüm kullanıcılar - ödeme ve iade turları bu listeyi dolaşır
+    fn market_users(env: &Env, market_id: u64) -> Vec<Address> {
+        let mut users = Vec::new(env);
+        for index in 0..Self::market_user_count(env, market_id) {
+            if let Some(user) = Self::market_user_at(env, market_id, index) {
+                users.push_back(user);
+            }
+        }
+        users
+    }
+
+    /// Pozisyon sahibini market kullanıcılarına, market'ı kullanıcının geçmişine ekle
+    /// (zaten varsa eklemez)
+    fn add_market_user(env: &Env, market_id: u64, user: &Address) {
+        let index_key = (MARKET_USERS, market_id, user.clone());
+        if !env.storage().persistent().has(&index_key) {
+            let count = Self::market_user_count(env, market_id);
+            env.storage().persistent().set(&(MARKET_USERS, market_id, count), user);
+            env.storage().persistent().set(&index_key, &count);
+            env.storage().persistent().set(&(MARKET_USERS, market_id), &(count + 1));
+        }
+        
+        let user_markets_key = (USER_MARKETS, user.clone());
+        let mut user_markets: Vec<u64> = env.storage().persistent()
+            .get(&user_markets_key).unwrap_or(Vec::new(env));
+        if !user_markets.contains(market_id) {
+            user_markets.push_back(market_id);
+            env.storage().persistent().set(&user_markets_key, &user_markets);
+        }
+    }
+    
+    /// Kullanıcının market'a yatırdığı / market'tan aldığı token toplamlarını güncelle (P&L için)
+    fn record_flow(env: &Env, user: &Address, market_id: u64, staked: i128, returned: i128) {
+        let key = (USER_FLOWS, user.clone(), market_id);
+        let (total_staked, total_returned): (i128, i128) = env.storage().persistent()
+            .get(&key).unwrap_or((0, 0));
+        env.storage().persistent().set(&key, &(total_staked + staked, total_returned + returned));
+    }
+    
+    /// Pozisyonu `amount` ödenmiş olarak işaretle ve PAYOUT / REFUND event'ini yayınla -
+    /// tüm kazanç ve iade yolları bunu kullanır
+    fn mark_paid(env: &Env, position: &mut UserBet, amount: i128, is_refund: bool) {
+        position.winnings = amount;
+        position.is_paid_out = true;
+        env.storage().persistent().set(
+            &Self::user_bet_key(position.market_id, &position.user, position.outcome),
+            position,
+        );
+        Self::record_flow(env, &position.user, position.market_id, 0, amount);
+        if is_refund {
+            events::refund(env, position, amount);
+        } else {
+            events::payout(env, position, amount);
+        }
+    }
+
+    /// Hiç pozisyonu kalmayan kullanıcıyı market kullanıcılarından çıkar - son sıradaki kullanıcı
+    /// boşalan sıraya taşınır
+    fn remove_market_user_if_empty(env: &Env, market: &Market, user: &Address) {
+        let market_id = market.id;
+        if !Self::get_user_positions(env.clone(), market_id, user.clone()).is_empty() {
+            return;
+        }
+        
+        let index_key = (MARKET_USERS, market_id, user.clone());
+        let index: u32 = match env.storage().persistent().get(&index_key) {
+            Some(index) => index,
+            None => return,
+        };
+        let last = Self::market_user_count(env, market_id) - 1;
+        if index != last {
+            if let Some(moved) = Self::market_user_at(env, market_id, last) {
+                env.storage().persistent().set(&(MARKET_USERS, market_id, index), &moved);
+                env.storage().persistent().set(&(MARKET_USERS, market_id, moved), &index);
+            }
+        }
+        env.storage().persistent().remove(&(MARKET_USERS, market_id, last));
+        env.storage().persistent().remove(&index_key);
+        env.storage().persistent().set(&(MARKET_USERS, market_id), &last);
+        
+        // Ödeme turları yalnızca market kullanıcılarını gezer - çıkanın sonucu (cash-out, satış) şimdi kesin
+        Self::apply_market_result(env, market, user, false);
+    }
+
+    /// Market'ın bahis limitlerini `user`'ın `outcome`'a yeni `amount`'u için kontrol et
+    fn check_bet_limits(env: &Env, market: &Market, user: &Address, outcome: u32, amount: i128) -> Result<(), Error> {
+        let limits = &market.limits;
+        
+        if amount < limits.min_bet {
+            return Err(Error::BetBelowMinimum);
+        }
+        if limits.max_bet > 0 && amount > limits.max_bet {
+            return Err(Error::BetAboveMaximum);
+        }
+        
+        let side_after = market.outcome_total(outcome) + amount;
+        Self::check_holder_limits(env, market, user, outcome, amount, side_after)?;
+        
+        let total_after = market.total_volume() + amount;
+        if limits.imbalance_limit_bps > 0
+            && total_after >= limits.share_checks_from
+            && side_after * payout::BPS_DENOMINATOR > limits.imbalance_limit_bps * total_after
+        {
+            return Err(Error::OutcomeSuspended);
+        }
+        Ok(())
+    }
+
+    /// `user`'ın `outcome`'daki payı `amount` artınca adres başına toplam ve taraf payı sınırlarını
+    /// kontrol et; `side_after` işlem sonrası outcome havuzu (devirlerde havuz değişmez)
+    fn check_holder_limits(
+        env: &Env,
+        market: &Market,
+        user: &Address,
+        outcome: u32,
+        amount: i128,
+        side_after: i128,
+    ) -> Result<(), Error> {
+        let limits = &market.limits;
+        
+        let mut user_total = 0i128;
+        let mut user_side = 0i128;
+        for position in Self::get_user_positions(env.clone(), market.id, user.clone()).iter() {
+            user_total += position.amount;
+            if position.outcome == outcome {
+                user_side = position.amount;
+            }
+        }
+        if limits.max_user_total > 0 && user_total + amount > limits.max_user_total {
+            return Err(Error::BetLimitExceeded);
+        }
+        
+        // Pay kontrolleri işlem sonrası havuzlara göre; küçük havuzdaki ilk bahisleri engellememek için
+        // ilgili havuz share_checks_from'a ulaşınca uygulanır
+        if limits.max_side_share_bps > 0
+            && side_after >= limits.share_checks_from
+            && (user_side + amount) * payout::BPS_DENOMINATOR > limits.max_side_share_bps * side_after
+        {
+            return Err(Error::BetLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Bahsi doğrula ve kaydet; `transfer_tokens` false ise token hareketi olmaz (test fonksiyonları)
+    fn record_bet(
+        env: &Env,
+        user: &Address,
+        market_id: u64,
+        amount: i128,
+        outcome: u32,
+        transfer_tokens: bool,
+    ) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        
+        let mut market = Self::load_market(env, market_id)?;
+        
+        // LMSR marketlerinde tutar değil pay alınır (buy_shares)
+        if let PricingMode::Lmsr(_) = market.pricing {
+            return Err(Error::UnsupportedPricingMode);
+        }
+        
+        // Outcome validation
+        let mut outcome_pool = market.outcomes.get(outcome)
+            .ok_or(Error::InvalidPrediction)?;
+        
+        let current_time = env.ledger().timestamp();
+        
+        match market.status_at(current_time) {
+            MarketStatus::Open => Self::snapshot_initial_price(env, &mut market),
+            MarketStatus::Scheduled => return Err(Error::MarketNotStarted),
+            MarketStatus::Closed => return Err(Error::MarketExpired),
+            MarketStatus::Voided => return Err(Error::MarketVoided),
+            _ => return Err(Error::MarketAlreadyResolved),
+        }
+        
+        Self::check_bet_limits(env, &market, user, outcome, amount)?;
+        
+        // Mevcut oranları hesapla
+        let current_odds = Self::calculate_improved_odds(env.clone(), market_id)?;
+        let odds_when_placed = current_odds.get(outcome).ok_or(Error::InvalidPrediction)?;
+        
+        // Sabit oran: oran kilitlenir, bankroll'un karşılayamayacağı bahis reddedilir
+        if let PricingMode::FixedOdds(config) = &market.pricing {
+            let liability = outcome_pool.liability + payout::fixed_odds_payout(amount, odds_when_placed)?;
+            let exposure = liability - (market.total_volume() + amount);
+            if exposure > config.max_exposure {
+                return Err(Error::ExposureLimitExceeded);
+            }
+            if exposure > market.bankroll {
+                return Err(Error::InsufficientBankroll);
+            }
+            outcome_pool.liability = liability;
+        }
+        
+        // Token transferi - kullanıcıdan kontrata
+        if transfer_tokens {
+            let token_client = token::Client::new(env, &market.betting_token);
+            token_client.transfer(user, &env.current_contract_address(), &amount);
+            Self::adjust_locked(env, &market.betting_token, amount);
+        }
+        
+        // Kullanıcının bu market'ta ilk bahsi ise market kullanıcıları listesine ekle
+        Self::add_market_user(env, market_id, user);
+        Self::record_flow(env, user, market_id, amount, 0);
+        
+        // Pozisyonu oluştur ya da mevcut pozisyona ekle
+        let user_bet_key = Self::user_bet_key(market_id, user, outcome);
+        let mut bet: UserBet = match env.storage().persistent().get(&user_bet_key) {
+            Some(bet) => bet,
+            None => {
+                outcome_pool.betters_count += 1;
+                UserBet {
+                    user: user.clone(),
+                    market_id,
+                    amount: 0,
+                    outcome,
+                    entries: Vec::new(env),
+                    is_paid_out: false,
+                    winnings: 0,
+                    shares: 0,
+                }
+            },
+        };
+        
+        bet.amount += amount;
+        bet.entries.push_back(BetEntry {
+            amount,
+            timestamp: current_time,
+            odds_when_placed,
+        });
+        
+        env.storage().persistent().set(&user_bet_key, &bet);
+        
+        // Market güncelle
+        outcome_pool.total_bets += amount;
+        market.outcomes.set(outcome, outcome_pool);
+        events::bet_placed(env, &market, user, outcome, amount, odds_when_placed, current_odds);
+        
+        Self::save_market(env, &market);
+        
+        Ok(())
+    }
+
+    /// Cash-out teklifi: pozisyonun bugünkü değeri (fee sonrası)
+    /// Değer = Σ entry.amount * odds_when_placed / current_odds, en fazla yatırılan miktar
+    pub fn get_cash_out_value(env: Env, user: Address, market_id: u64, outcome: u32) -> Result<i128, Error> {
+        let market = Self::get_market(env.clone(), market_id)
+            .ok_or(Error::MarketNotFound)?;
+        
+        // Sabit oranlı pozisyonlar bankroll'a karşı kilitli - havuzdan çıkış yok
+        if !market.is_pari_mutuel() {
+            return Err(Error::UnsupportedPricingMode);
+        }
+        
+        let user_bet: UserBet = env.storage().persistent()
+            .get(&Self::user_bet_key(market_id, &user, outcome))
+            .ok_or(Error::PositionNotFound)?;
+        
+        let current_odds = Self::calculate_improved_odds(env.clone(), market_id)?
+            .get(outcome).ok_or(Error::InvalidPrediction)?;
+        
+        let mut value = 0i128;
+        for entry in user_bet.entries.iter() {
+            value += (entry.amount * entry.odds_when_placed) / current_odds;
+        }
+        
+        // Havuzdan yatırılandan fazlası çekilemez - diğer bahisçilerin payı korunur
+        let value = value.min(user_bet.amount);
+        let fee = (value * CASH_OUT_FEE_BPS) / 10000;
+        Ok(value - fee)
+    }
+
+    /// Pozisyonu kapanıştan önce sat (cash-out)
+    pub fn cash_out(env: Env, user: Address, market_id: u64, outcome: u32) -> Result<i128, Error> {
+        user.require_auth();
+        
+        let mut market = Self::load_market(&env, market_id)?;
+        
+        if market.is_voided() {
+            return Err(Error::MarketVoided);
+        }
+        
+        if market.is_resolved() {
+            return Err(Error::MarketAlreadyResolved);
+        }
+        
+        let current_time = env.ledger().timestamp();
+        if current_time + CASH_OUT_CUTOFF_SECS > market.close_time {
+            return Err(Error::CashOutWindowClosed);
+        }
+        
+        let user_bet_key = Self::user_bet_key(market_id, &user, outcome);
+        let user_bet: UserBet = env.storage().persistent()
+            .get(&user_bet_key)
+            .ok_or(Error::PositionNotFound)?;
+        
+        let payout = Self::get_cash_out_value(env.clone(), user.clone(), market_id, outcome)?;
+        
+        // Token transferi - kontrattan kullanıcıya
+        let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&env.current_contract_address(), &user, &payout);
+        Self::adjust_locked(&env, &market.betting_token, -payout);
+        
+        Self::record_flow(&env, &user, market_id, 0, payout);
+        
+        // Pozisyonu kaldır ve havuzları güncelle
+        env.storage().persistent().remove(&user_bet_key);
+        
+        let mut outcome_pool = market.outcomes.get(outcome).ok_or(Error::InvalidPrediction)?;
+        outcome_pool.total_bets -= user_bet.amount;
+        outcome_pool.betters_count -= 1;
+        market.outcomes.set(outcome, outcome_pool);
+        market.cash_out_surplus += user_bet.amount - payout;
+        
+        Self::save_market(&env, &market);
+        
+        // Başka pozisyonu kalmadıysa market kullanıcılarından çıkar
+        Self::remove_market_user_if_empty(&env, &market, &user);
+        Self::remove_listing(&env, market_id, &user, outcome);
+        
+        events::cashed_out(&env, market_id, &user, outcome, payout);
+        
+        Ok(payout)
+    }
+
+    /// Pozisyonun transfer edilebilir olduğunu kontrol et (çözülmemiş ve kapanmamış market)
+    fn require_tradable(env: &Env, market_id: u64) -> Result<Market, Error> {
+        let market = Self::get_market(env.clone(), market_id)
+            .ok_or(Error::MarketNotFound)?;
+        
+        match market.status_at(env.ledger().timestamp()) {
+            MarketStatus::Open => Ok(market),
+            MarketStatus::Scheduled => Err(Error::MarketNotStarted),
+            MarketStatus::Closed => Err(Error::MarketExpired),
+            MarketStatus::Voided => Err(Error::MarketVoided),
+            _ => Err(Error::MarketAlreadyResolved),
+        }
+    }
+
+    /// Pozisyonu `from`'dan `to`'ya taşı; `to`'nun aynı outcome'da pozisyonu varsa birleştir
+    fn move_position(env: &Env, market: &Market, from: &Address, to: &Address, outcome: u32) -> Result<(), Error> {
+        if from == to {
+            return Err(Error::InvalidTransfer);
+        }
+        
+        let market_id = market.id;
+        let from_key = Self::user_bet_key(market_id, from, outcome);
+        let position: UserBet = env.storage().persistent()
+            .get(&from_key)
+            .ok_or(Error::PositionNotFound)?;
+        
+        // Devralan adres de limitlere tabi - yoksa adres başına sınırlar devirle aşılır
+        Self::check_holder_limits(env, market, to, outcome, position.amount, market.outcome_total(outcome))?;
+        
+        let to_key = Self::user_bet_key(market_id, to, outcome);
+        let merged = match env.storage().persistent().get::<(Symbol, u64, Address, u32), UserBet>(&to_key) {
+            Some(mut existing) => {
+                // İki sahip tek pozisyonda birleşti - outcome'daki sahip sayısı azalır
+                let mut market = Self::load_market(env, market_id)?;
+                let mut outcome_pool = market.outcomes.get(outcome).ok_or(Error::InvalidPrediction)?;
+                outcome_pool.betters_count -= 1;
+                market.outcomes.set(outcome, outcome_pool);
+                Self::save_market(env, &market);
+                
+                existing.amount += position.amount;
+                existing.shares += position.shares;
+                existing.entries.append(&position.entries);
+                existing
+            },
+            None => UserBet {
+                user: to.clone(),
+                ..position
+            },
+        };
+        
+        env.storage().persistent().remove(&from_key);
+        env.storage().persistent().set(&to_key, &merged);
+        
+        Self::remove_listing(env, market_id, from, outcome);
+        Self::add_market_user(env, market_id, to);
+        Self::remove_market_user_if_empty(env, market, from);
+        
+        Ok(())
+    }
+
+    /// Pozisyonu başka bir adrese devret
+    pub fn transfer_position(env: Env, from: Address, to: Address, market_id: u64, outcome: u32) -> Result<(), Error> {
+        from.require_auth();
+        
+        let market = Self::require_tradable(&env, market_id)?;
+        Self::move_position(&env, &market, &from, &to, outcome)?;
+        
+        events::position_transferred(&env, market_id, &from, &to, outcome);
+        
+        Ok(())
+    }
+
+    /// Pozisyonu sabit fiyatla satışa çıkar (aynı pozisyon için önceki ilanın yerine geçer)
+    pub fn list_position(env: Env, seller: Address, market_id: u64, outcome: u32, price: i128) -> Result<(), Error> {
+        seller.require_auth();
+        
+        if price <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        
+        Self::require_tradable(&env, market_id)?;
+        
+        if !env.storage().persistent().has(&Self::user_bet_key(market_id, &seller, outcome)) {
+            return Err(Error::PositionNotFound);
+        }
+        
+        Self::remove_listing(&env, market_id, &seller, outcome);
+        
+        let listings_key = (LISTINGS, market_id);
+        let mut listings: Vec<PositionListing> = env.storage().persistent()
+            .get(&listings_key).unwrap_or(Vec::new(&env));
+        listings.push_back(PositionListing {
+            seller: seller.clone(),
+            market_id,
+            outcome,
+            price,
+        });
+        env.storage().persistent().set(&listings_key, &listings);
+        
+        events::position_listed(&env, market_id, &seller, outcome, price);
+        
+        Ok(())
+    }
+
+    /// Satış ilanını geri çek
+    pub fn cancel_listing(env: Env, seller: Address, market_id: u64, outcome: u32) -> Result<(), Error> {
+        seller.require_auth();
+        
+        if !Self::remove_listing(&env, market_id, &seller, outcome) {
+            return Err(Error::ListingNotFound);
+        }
+        
+        events::listing_cancelled(&env, market_id, &seller, outcome);
+        
+        Ok(())
+    }
+
+    /// İlandaki pozisyonu satın al - ödeme doğrudan satıcıya gider
+    pub fn buy_position(env: Env, buyer: Address, seller: Address, market_id: u64, outcome: u32) -> Result<i128, Error> {
+        buyer.require_auth();
+        
+        let market = Self::require_tradable(&env, market_id)?;
+        
+        let listing = Self::get_listings(env.clone(), market_id)
+            .iter()
+            .find(|l| l.seller == seller && l.outcome == outcome)
+            .ok_or(Error::ListingNotFound)?;
+        
+        let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&buyer, &seller, &listing.price);
+        
+        Self::move_position(&env, &market, &seller, &buyer, outcome)?;
+        Self::record_flow(&env, &buyer, market_id, listing.price, 0);
+        Self::record_flow(&env, &seller, market_id, 0, listing.price);
+        
+        events::position_sold(&env, market_id, &seller, &buyer, outcome, listing.price);
+        
+        Ok(listing.price)
+    }
+
+    /// Market'taki aktif satış ilanları
+    pub fn get_listings(env: Env, market_id: u64) -> Vec<PositionListing> {
+        env.storage().persistent()
+            .get(&(LISTINGS, market_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// İlanı kaldır; ilan varsa true döner
+    fn remove_listing(env: &Env, market_id: u64, seller: &Address, outcome: u32) -> bool {
+        let listings_key = (LISTINGS, market_id);
+        let mut listings: Vec<PositionListing> = env.storage().persistent()
+            .get(&listings_key).unwrap_or(Vec::new(env));
+        
+        for i in 0..listings.len() {
+            let listing = listings.get_unchecked(i);
+            if listing.seller == *seller && listing.outcome == outcome {
+                listings.remove(i);
+                env.storage().persistent().set(&listings_key, &listings);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Sabit oranlı / LMSR markete bankroll yatır - sadece market creator'ı
+    pub fn fund_bankroll(env: Env, provider: Address, market_id: u64, amount: i128) -> Result<i128, Error> {
+        provider.require_auth();
+        
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        
+        let mut market = Self::load_market(&env, market_id)?;
+        
+        if market.is_pari_mutuel() {
+            return Err(Error::UnsupportedPricingMode);
+        }
+        
+        if provider != market.creator {
+            return Err(Error::NotAuthorized);
+        }
+        
+        if market.is_voided() {
+            return Err(Error::MarketVoided);
+        }
+        
+        if market.is_resolved() {
+            return Err(Error::MarketAlreadyResolved);
+        }
+        
+        let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&provider, &env.current_contract_address(), &amount);
+        Self::adjust_locked(&env, &market.betting_token, amount);
+        
+        market.bankroll += amount;
+        let bankroll = market.bankroll;
+        Self::save_market(&env, &market);
+        
+        events::bankroll_added(&env, &market, &provider, amount);
+        
+        Ok(bankroll)
+    }
+
+    /// Tohum likiditesinin payını creator'a öde (ödeme turları bunu kendisi yapar; iptal ya da
+    /// ödeme turu olmadan claim edilen marketler için)
+    pub fn withdraw_liquidity(env: Env, creator: Address, market_id: u64) -> Result<i128, Error> {
+        creator.require_auth();
+        
+        let mut market = Self::load_market(&env, market_id)?;
+        
+        if creator != market.creator {
+            return Err(Error::NotAuthorized);
+        }
+        
+        if market.liquidity.is_empty() || market.liquidity_returned {
+            return Err(Error::InsufficientBalance);
+        }
+        
+        if !market.is_voided() {
+            Self::require_payable(&env, &market)?;
+        }
+        
+        let amount = payout::liquidity_payout(&market)?;
+        let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&env.current_contract_address(), &creator, &amount);
+        Self::adjust_locked(&env, &market.betting_token, -amount);
+        
+        market.liquidity_returned = true;
+        events::liquidity_returned(&env, &market, amount);
+        
+        Self::save_market(&env, &market);
+        
+        Ok(amount)
+    }
+
+    /// Ödeme turu sırasında tohum payını creator'a gönder; transfer başarısızsa
+    /// creator daha sonra `withdraw_liquidity` ile çeker
+    fn return_liquidity(env: &Env, market: &mut Market) -> Result<(), Error> {
+        if market.liquidity.is_empty() || market.liquidity_returned {
+            return Ok(());
+        }
+        
+        let amount = payout::liquidity_payout(market)?;
+        let token_client = token::Client::new(env, &market.betting_token);
+        if token_client.try_transfer(&env.current_contract_address(), &market.creator, &amount).is_ok() {
+            Self::adjust_locked(env, &market.betting_token, -amount);
+            market.liquidity_returned = true;
+            events::liquidity_returned(env, market, amount);
+        }
+        Ok(())
+    }
+
+    /// Çözülmüş sabit oranlı / LMSR marketten kazananların alacağı dışında kalan her şeyi creator'a geri öde
+    pub fn withdraw_bankroll(env: Env, provider: Address, market_id: u64) -> Result<i128, Error> {
+        provider.require_auth();
+        
+        let mut market = Self::load_market(&env, market_id)?;
+        
+        if market.is_pari_mutuel() {
+            return Err(Error::UnsupportedPricingMode);
+        }
+        
+        if provider != market.creator {
+            return Err(Error::NotAuthorized);
+        }
+        
+        // Kazananlara ödenen/ödenecek tutar (iptalde tüm stake'ler) kontratta kalır
+        let reserved = if market.is_voided() {
+            Self::position_stakes(&env, &market)
+        } else {
+            let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
+            Self::require_payable(&env, &market)?;
+            market.outcome_liability(winning_outcome)
+        };
+        let surplus = market.bankroll + market.total_volume() - reserved;
+        if surplus <= 0 {
+            return Err(Error::InsufficientBankroll);
+        }
+        
+        let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&env.current_contract_address(), &provider, &surplus);
+        Self::adjust_locked(&env, &market.betting_token, -surplus);
+        
+        // Bankroll negatife düşebilir: house stake'leri de almış demektir
+        market.bankroll -= surplus;
+        Self::save_market(&env, &market);
+        
+        events::bankroll_withdrawn(&env, &market, &provider, surplus);
+        
+        Ok(surplus)
+    }
+
+    /// LMSR pay alım fiyatı (token cinsinden)
+    pub fn quote_buy_shares(env: Env, market_id: u64, outcome: u32, shares: i128) -> Result<i128, Error> {
+        let market = Self::get_market(env, market_id).ok_or(Error::MarketNotFound)?;
+        let liquidity = Self::lmsr_liquidity(&market, outcome, shares)?;
+        lmsr::buy_cost(&market, liquidity, outcome, shares)
+    }
+
+    /// LMSR pay satış geliri (token cinsinden)
+    pub fn quote_sell_shares(env: Env, market_id: u64, outcome: u32, shares: i128) -> Result<i128, Error> {
+        let market = Self::get_market(env, market_id).ok_or(Error::MarketNotFound)?;
+        let liquidity = Self::lmsr_liquidity(&market, outcome, shares)?;
+        lmsr::sell_proceeds(&market, liquidity, outcome, shares)
+    }
+
+    /// LMSR market'ın likidite parametresi; işlem parametrelerini de doğrular
+    fn lmsr_liquidity(market: &Market, outcome: u32, shares: i128) -> Result<i128, Error> {
+        let liquidity = match market.pricing {
+            PricingMode::Lmsr(liquidity) => liquidity,
+            _ => return Err(Error::UnsupportedPricingMode),
+        };
+        if outcome >= market.outcomes.len() {
+            return Err(Error::InvalidPrediction);
+        }
+        if shares <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Ok(liquidity)
+    }
+
+    /// LMSR'den pay al - maliyet `max_cost`'u geçerse işlem reddedilir
+    pub fn buy_shares(
+        env: Env,
+        user: Address,
+        market_id: u64,
+        outcome: u32,
+        shares: i128,
+        max_cost: i128,
+    ) -> Result<i128, Error> {
+        user.require_auth();
+        
+        let mut market = Self::require_tradable(&env, market_id)?;
+        Self::snapshot_initial_price(&env, &mut market);
+        let liquidity = Self::lmsr_liquidity(&market, outcome, shares)?;
+        
+        // Creator'ın b * ln(n) sübvansiyonu yatırılmadan işlem açılmaz
+        if market.bankroll < lmsr::max_loss(liquidity, market.outcomes.len())? {
+            return Err(Error::InsufficientBankroll);
+        }
+        
+        let cost = lmsr::buy_cost(&market, liquidity, outcome, shares)?;
+        if cost > max_cost {
+            return Err(Error::SlippageExceeded);
+        }
+        Self::check_bet_limits(&env, &market, &user, outcome, cost)?;
+        
+        // BET event'i için işlem öncesi AMM oranları
+        let current_odds = Self::calculate_improved_odds(env.clone(), market_id)?;
+        
+        let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&user, &env.current_contract_address(), &cost);
+        Self::adjust_locked(&env, &market.betting_token, cost);
+        
+        let user_bet_key = Self::user_bet_key(market_id, &user, outcome);
+        let mut outcome_pool = market.outcomes.get(outcome).ok_or(Error::InvalidPrediction)?;
+        let mut position: UserBet = match env.storage().persistent().get(&user_bet_key) {
+            Some(position) => position,
+            None => {
+                outcome_pool.betters_count += 1;
+                UserBet {
+                    user: user.clone(),
+                    market_id,
+                    amount: 0,
+                    outcome,
+                    entries: Vec::new(&env),
+                    is_paid_out: false,
+                    winnings: 0,
+                    shares: 0,
+                }
+            },
+        };
+        
+        // Entry oranı = pay başına ödeme / maliyet
+        let odds_when_placed = shares * payout::ODDS_DENOMINATOR / cost;
+        position.amount += cost;
+        position.shares += shares;
+        position.entries.push_back(BetEntry {
+            amount: cost,
+            timestamp: env.ledger().timestamp(),
+            odds_when_placed,
+        });
+        env.storage().persistent().set(&user_bet_key, &position);
+        Self::add_market_user(&env, market_id, &user);
+        Self::record_flow(&env, &user, market_id, cost, 0);
+        
+        outcome_pool.total_bets += cost;
+        outcome_pool.liability += shares;
+        market.outcomes.set(outcome, outcome_pool);
+        
+        events::bet_placed(&env, &market, &user, outcome, cost, odds_when_placed, current_odds);
+        Self::save_market(&env, &market);
+        
+        events::shares_bought(&env, market_id, &user, outcome, shares, cost);
+        
+        Ok(cost)
+    }
+
+    /// LMSR'ye pay sat - gelir `min_proceeds`'in altındaysa işlem reddedilir
+    pub fn sell_shares(
+        env: Env,
+        user: Address,
+        market_id: u64,
+        outcome: u32,
+        shares: i128,
+        min_proceeds: i128,
+    ) -> Result<i128, Error> {
+        user.require_auth();
+        
+        let mut market = Self::require_tradable(&env, market_id)?;
+        let liquidity = Self::lmsr_liquidity(&market, outcome, shares)?;
+        
+        let user_bet_key = Self::user_bet_key(market_id, &user, outcome);
+        let mut position: UserBet = env.storage().persistent()
+            .get(&user_bet_key)
+            .ok_or(Error::PositionNotFound)?;
+        
+        if shares > position.shares {
+            return Err(Error::InvalidAmount);
+        }
+        
+        let proceeds = lmsr::sell_proceeds(&market, liquidity, outcome, shares)?;
+        if proceeds < min_proceeds {
+            return Err(Error::SlippageExceeded);
+        }
+        
+        let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&env.current_contract_address(), &user, &proceeds);
+        Self::adjust_locked(&env, &market.betting_token, -proceeds);
+        Self::record_flow(&env, &user, market_id, 0, proceeds);
+        
+        let mut outcome_pool = market.outcomes.get(outcome).ok_or(Error::InvalidPrediction)?;
+        outcome_pool.total_bets -= proceeds;
+        outcome_pool.liability -= shares;
+        
+        let closes_position = shares == position.shares;
+        if closes_position {
+            outcome_pool.betters_count -= 1;
+            env.storage().persistent().remove(&user_bet_key);
+        } else {
+            // Maliyet tabanı satılan pay oranında azalır
+            position.amount -= position.amount * shares / position.shares;
+            position.shares -= shares;
+            env.storage().persistent().set(&user_bet_key, &position);
+        }
+        market.outcomes.set(outcome, outcome_pool);
+        
+        Self::save_market(&env, &market);
+        
+        // İlan satılan payları da kapsıyordu - kalan pozisyon yeniden listelenmeli
+        Self::remove_listing(&env, market_id, &user, outcome);
+        if closes_position {
+            Self::remove_market_user_if_empty(&env, &market, &user);
+        }
+        
+        events::shares_sold(&env, market_id, &user, outcome, shares, proceeds);
+        
+        Ok(proceeds)
+    }
+
+    /// Kontrat admin'i deploy anında ayarlanır - sonradan ilk çağıran admin olamaz
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().persistent().set(&ADMIN, &admin);
+    }
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&ADMIN)
+    }
+
+    /// Çağıranın kayıtlı admin olduğunu doğrula
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+        
+        let stored: Address = env.storage().persistent()
+            .get(&ADMIN).ok_or(Error::NotAuthorized)?;
+        if stored != *admin {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    /// Admin, hesaba yetki verir
+    pub fn grant_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        
+        env.storage().persistent().set(&(ROLES, role, account.clone()), &true);
+        events::role_granted(&env, &admin, &account, role);
+        Ok(())
+    }
+
+    /// Admin, hesabın yetkisini geri alır
+    pub fn revoke_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        
+        env.storage().persistent().remove(&(ROLES, role, account.clone()));
+        events::role_revoked(&env, &admin, &account, role);
+        Ok(())
+    }
+
+    /// Admin yetkisini başka bir adrese devret
+    pub fn transfer_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        new_admin.require_auth();
+        
+        env.storage().persistent().set(&ADMIN, &new_admin);
+        
+        events::admin_transferred(&env, &admin, &new_admin);
+        Ok(())
+    }
+
+    /// Hesabın yetkisi var mı (admin her zaman true)
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        if Self::get_admin(env.clone()) == Some(account.clone()) {
+            return true;
+        }
+        env.storage().persistent().get(&(ROLES, role, account)).unwrap_or(false)
+    }
+
+    /// Çağıranın yetkisini doğrula
+    fn require_role(env: &Env, account: &Address, role: Role) -> Result<(), Error> {
+        account.require_auth();
+        
+        if !Self::has_role(env.clone(), role, account.clone()) {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    /// Komisyonun market creator'ına giden payını ayarla (basis points)
+    pub fn set_creator_fee_share(env: Env, admin: Address, share_bps: i128) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
         
-        env.storage().persistent().set(&MARKETS, &markets);
-        env.storage().persistent().set(&MARKET_COUNTER, &market_id);
+        if !(0..=payout::BPS_DENOMINATOR).contains(&share_bps) {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().persistent().set(&CREATOR_FEE_SHARE, &share_bps);
         
-        Ok(market_id)
+        events::config_changed(&env, &admin, events::ConfigChange::CreatorFeeShare(share_bps));
+        Ok(())
     }
 
-    /// ADİL ÖDÜL SİSTEMİ - Pool-Based Fair Odds Calculation
-    /// Bu sistem odds'ları pool'daki gerçek para miktarına göre hesaplar
-    pub fn calculate_improved_odds(env: Env, market_id: u64) -> Result<Odds, Error> {
-        let markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-            
-        let market = markets.get(market_id)
-            .ok_or(Error::MarketNotFound)?;
-        
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
+    /// Referanslı bahislerin komisyonundan referansa giden payı ayarla (basis points)
+    pub fn set_referral_share(env: Env, admin: Address, share_bps: i128) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
         
-        // Başlangıç odds'ları (hiç bahis yoksa)
-        if total_volume == 0 {
-            return Ok(Odds {
-                up_odds: 2000,    // 2.0x
-                down_odds: 2000,  // 2.0x  
-                stable_odds: 2000, // 2.0x
-            });
+        if !(0..=payout::BPS_DENOMINATOR).contains(&share_bps) {
+            return Err(Error::InvalidAmount);
         }
+        env.storage().persistent().set(&REFERRAL_SHARE, &share_bps);
         
-        // Komisyon sonrası ödül havuzu
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
-        
-        // **YENİ ADİL SİSTEM**: Her taraf için gerçekçi odds hesapla
-        // Formül: Odds = (Ödül Havuzu / Bahis Miktarı) ama maksimum limitle
+        events::config_changed(&env, &admin, events::ConfigChange::ReferralShare(share_bps));
+        Ok(())
+    }
+
+    /// Referans veren adresin token'daki istatistikleri ve ödül durumu
+    pub fn get_referral_stats(env: Env, referrer: Address, token: Address) -> ReferralStats {
+        env.storage().persistent()
+            .get(&(REFERRAL_STATS, referrer, token))
+            .unwrap_or(ReferralStats {
+                referred_bets: 0,
+                referred_volume: 0,
+                rewards_earned: 0,
+                rewards_claimed: 0,
+            })
+    }
+
+    /// Çekilmemiş referans ödüllerini çek
+    pub fn claim_referral_rewards(env: Env, referrer: Address, token: Address) -> Result<i128, Error> {
+        referrer.require_auth();
+        
+        let mut stats = Self::get_referral_stats(env.clone(), referrer.clone(), token.clone());
+        let claimable = stats.rewards_earned - stats.rewards_claimed;
+        if claimable == 0 {
+            return Err(Error::InsufficientBalance);
+        }
+        stats.rewards_claimed = stats.rewards_earned;
+        env.storage().persistent().set(&(REFERRAL_STATS, referrer.clone(), token.clone()), &stats);
         
-        let up_odds = if market.total_up_bets > 0 {
-            // Pool'daki para / Up bahisleri = gerçek payout oranı
-            let raw_odds = (prize_pool * 1000) / market.total_up_bets;
-            let max_realistic_odds = 5000i128; // Maksimum 5.0x
-            let min_odds = 1100i128; // Minimum 1.1x (garantili kar)
-            raw_odds.max(min_odds).min(max_realistic_odds)
-        } else {
-            2000 // Default 2.0x
-        };
+        let owed_key = (REFERRAL_OWED, token.clone());
+        let owed: i128 = env.storage().persistent().get(&owed_key).unwrap_or(0);
+        env.storage().persistent().set(&owed_key, &(owed - claimable));
         
-        let down_odds = if market.total_down_bets > 0 {
-            let raw_odds = (prize_pool * 1000) / market.total_down_bets;
-            let max_realistic_odds = 5000i128;
-            let min_odds = 1100i128;
-            raw_odds.max(min_odds).min(max_realistic_odds)
-        } else {
-            2000
-        };
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &referrer, &claimable);
         
-        let stable_odds = if market.total_stable_bets > 0 {
-            let raw_odds = (prize_pool * 1000) / market.total_stable_bets;
-            let max_realistic_odds = 5000i128;
-            let min_odds = 1100i128;
-            raw_odds.max(min_odds).min(max_realistic_odds)
-        } else {
-            2000
-        };
+        events::referral_claimed(&env, &token, &referrer, claimable);
         
-        Ok(Odds {
-            up_odds,
-            down_odds,
-            stable_odds,
-        })
+        Ok(claimable)
     }
-    /// Bahis koy - Token transferi ile
-    pub fn place_bet(
-        env: Env,
-        user: Address,
-        market_id: u64,
-        amount: i128,
-        prediction: u32, // 0=Up, 1=Down, 2=Stable
-    ) -> Result<(), Error> {
-        user.require_auth();
+
+    /// Token için hazinede birikmiş komisyon
+    pub fn get_treasury_balance(env: Env, token: Address) -> i128 {
+        env.storage().persistent().get(&(TREASURY, token)).unwrap_or(0)
+    }
+
+    /// Creator'ın çekebileceği komisyon payı
+    pub fn get_creator_fees(env: Env, creator: Address, token: Address) -> i128 {
+        env.storage().persistent().get(&(CREATOR_FEES, creator, token)).unwrap_or(0)
+    }
+
+    /// Birikmiş komisyondan çekim - kullanıcı fonlarına dokunulamaz
+    pub fn withdraw_commission(env: Env, treasurer: Address, token: Address, to: Address, amount: i128) -> Result<i128, Error> {
+        Self::require_role(&env, &treasurer, Role::Treasurer)?;
         
         if amount <= 0 {
             return Err(Error::InvalidAmount);
         }
         
-        // Prediction validation
-        if prediction > 2 {
-            return Err(Error::InvalidPrediction);
+        let treasury_key = (TREASURY, token.clone());
+        let accrued: i128 = env.storage().persistent().get(&treasury_key).unwrap_or(0);
+        if amount > accrued {
+            return Err(Error::InsufficientContractBalance);
         }
+        env.storage().persistent().set(&treasury_key, &(accrued - amount));
         
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-            
-        let mut market = markets.get(market_id)
-            .ok_or(Error::MarketNotFound)?;
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
         
-        let current_time = env.ledger().timestamp();
+        events::fee_withdrawn(&env, &token, events::FeeWithdrawnEvent {
+            recipient: to,
+            amount,
+            creator_share: false,
+            remaining: accrued - amount,
+        });
         
-        if current_time < market.start_time {
-            return Err(Error::MarketNotStarted);
+        Ok(accrued - amount)
+    }
+
+    /// Creator komisyon payını çeker
+    pub fn claim_creator_fees(env: Env, creator: Address, token: Address) -> Result<i128, Error> {
+        creator.require_auth();
+        
+        let creator_key = (CREATOR_FEES, creator.clone(), token.clone());
+        let owed: i128 = env.storage().persistent().get(&creator_key).unwrap_or(0);
+        if owed == 0 {
+            return Err(Error::InsufficientBalance);
         }
+        env.storage().persistent().remove(&creator_key);
         
-        if current_time > market.end_time {
-            return Err(Error::MarketExpired);
+        let total_key = (CREATOR_OWED, token.clone());
+        let total: i128 = env.storage().persistent().get(&total_key).unwrap_or(0);
+        env.storage().persistent().set(&total_key, &(total - owed));
+        
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &creator, &owed);
+        
+        events::fee_withdrawn(&env, &token, events::FeeWithdrawnEvent {
+            recipient: creator,
+            amount: owed,
+            creator_share: true,
+            remaining: 0,
+        });
+        
+        Ok(owed)
+    }
+
+    /// Token başına marketlerde kilitli tutarı güncelle - market hesabına giren ya da çıkan her
+    /// transfer ve hazineye ayrılan her tutar bunu çağırır
+    fn adjust_locked(env: &Env, token: &Address, delta: i128) {
+        let key = (LOCKED, token.clone());
+        let locked: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(locked + delta));
+    }
+
+    /// Hazine, market yükümlülükleri ve kontrat bakiyesi mutabakatı - tutarlar token başına
+    /// tutulan toplamlardan okunur, market sayısından bağımsızdır
+    pub fn get_treasury_report(env: Env, token: Address) -> TreasuryReport {
+        let accrued_commission = Self::get_treasury_balance(env.clone(), token.clone());
+        let creator_fees: i128 = env.storage().persistent().get(&(CREATOR_OWED, token.clone())).unwrap_or(0);
+        let referral_rewards: i128 = env.storage().persistent().get(&(REFERRAL_OWED, token.clone())).unwrap_or(0);
+        let locked_in_markets: i128 = env.storage().persistent().get(&(LOCKED, token.clone())).unwrap_or(0);
+        let contract_balance = Self::get_contract_balance(env, token.clone());
+        
+        TreasuryReport {
+            token,
+            accrued_commission,
+            creator_fees,
+            referral_rewards,
+            locked_in_markets,
+            contract_balance,
+            unallocated: contract_balance - accrued_commission - creator_fees - referral_rewards - locked_in_markets,
         }
+    }
+
+    /// İtiraz süresi ve teminatını ayarla
+    pub fn set_dispute_config(env: Env, admin: Address, window_secs: u64, bond: i128) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
         
-        if market.is_resolved {
-            return Err(Error::MarketAlreadyResolved);
+        if bond < 0 {
+            return Err(Error::InvalidAmount);
         }
+        let config = DisputeConfig { window_secs, bond };
+        env.storage().persistent().set(&DISPUTE_CONFIG, &config);
         
-        let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-        if env.storage().persistent().has(&user_bet_key) {
-            return Err(Error::UserAlreadyBet);
+        events::config_changed(&env, &admin, events::ConfigChange::Dispute(config));
+        Ok(())
+    }
+
+    pub fn get_dispute_config(env: Env) -> DisputeConfig {
+        env.storage().persistent()
+            .get(&DISPUTE_CONFIG)
+            .unwrap_or(DisputeConfig { window_secs: 0, bond: 0 })
+    }
+
+    pub fn get_dispute(env: Env, market_id: u64) -> Option<Dispute> {
+        env.storage().persistent().get(&(DISPUTES, market_id))
+    }
+
+    /// Ödemeler itiraz süresi dolana ve açık itiraz karara bağlanana kadar bekler
+    fn require_payable(env: &Env, market: &Market) -> Result<(), Error> {
+        match market.status_at(env.ledger().timestamp()) {
+            MarketStatus::Resolved | MarketStatus::Settled => Ok(()),
+            MarketStatus::Resolving => Err(Error::DisputeWindowOpen),
+            MarketStatus::Disputed => Err(Error::MarketDisputed),
+            MarketStatus::Voided => Err(Error::MarketVoided),
+            _ => Err(Error::MarketNotResolved),
         }
+    }
+
+    /// Çözüme itiraz et - kanıt Reflector'ın end_time'daki kaydı, teminat kontrata kilitlenir
+    pub fn dispute_resolution(env: Env, challenger: Address, market_id: u64) -> Result<Dispute, Error> {
+        challenger.require_auth();
         
-        // Token transferi - kullanıcıdan kontrata
-        let token_client = token::Client::new(&env, &market.betting_token);
-        token_client.transfer(&user, &env.current_contract_address(), &amount);
+        let mut market = Self::load_market(&env, market_id)?;
         
-        // Kullanıcıyı market kullanıcıları listesine ekle
-        let market_users_key = (MARKET_USERS, market_id);
-        let mut market_users: Vec<Address> = env.storage().persistent()
-            .get(&market_users_key).unwrap_or(Vec::new(&env));
-        market_users.push_back(user.clone());
-        env.storage().persistent().set(&market_users_key, &market_users);
+        // TWAP / Average kapanışı her çözüm yolunda oracle ortalamasıdır (admin fiyatı kullanılmaz);
+        // end_time'daki tek bir spot kayıt bunu çürütemez, aksine manipülasyona kapı açar
+        if !matches!(market.settlement_mode, SettlementMode::Spot) {
+            return Err(Error::InvalidDispute);
+        }
         
-        // Mevcut oranları hesapla
-        let current_odds = Self::calculate_improved_odds(env.clone(), market_id)?;
-        let odds_when_placed = match prediction {
-            0 => current_odds.up_odds,    // Up
-            1 => current_odds.down_odds,  // Down
-            2 => current_odds.stable_odds, // Stable
-            _ => return Err(Error::InvalidPrediction),
-        };
+        // Market başına tek itiraz
+        if Self::get_dispute(env.clone(), market_id).is_some() {
+            return Err(Error::InvalidDispute);
+        }
         
-        // Bahis kaydet
-        let bet = UserBet {
-            user: user.clone(),
-            market_id,
-            amount,
-            prediction,
-            timestamp: current_time,
-            odds_when_placed,
-            is_paid_out: false,
-            winnings: 0,
-        };
+        market.transition(env.ledger().timestamp(), MarketStatus::Disputed)?;
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
         
-        env.storage().persistent().set(&user_bet_key, &bet);
+        let evidence = market.feed
+            .price_at(&Self::get_oracle_client(&env), market.end_time)
+            .ok_or(Error::OraclePriceUnavailable)?;
         
-        // Market güncelle
-        match prediction {
-            0 => { // Up
-                market.total_up_bets += amount;
-                market.up_betters_count += 1;
-            },
-            1 => { // Down
-                market.total_down_bets += amount;
-                market.down_betters_count += 1;
-            },
-            2 => { // Stable
-                market.total_stable_bets += amount;
-                market.stable_betters_count += 1;
-            },
-            _ => return Err(Error::InvalidPrediction),
+        // Kanıt aynı sonucu gösteriyorsa itiraz edilecek bir şey yok
+        let proposed_outcome = Self::determine_winning_outcome(&market, evidence.price)?;
+        if proposed_outcome == winning_outcome {
+            return Err(Error::InvalidDispute);
+        }
+        
+        let bond = Self::get_dispute_config(env.clone()).bond;
+        if bond > 0 {
+            let token_client = token::Client::new(&env, &market.betting_token);
+            token_client.transfer(&challenger, &env.current_contract_address(), &bond);
+            Self::adjust_locked(&env, &market.betting_token, bond);
         }
         
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
+        let dispute = Dispute {
+            challenger: challenger.clone(),
+            bond,
+            evidence_price: evidence.price,
+            evidence_timestamp: evidence.timestamp,
+            proposed_outcome,
+            opened_at: env.ledger().timestamp(),
+            is_settled: false,
+            overturned: false,
+        };
+        env.storage().persistent().set(&(DISPUTES, market_id), &dispute);
+        Self::save_market(&env, &market);
         
-        Ok(())
+        events::disputed(&env, market_id, &dispute);
+        
+        Ok(dispute)
     }
 
-    /// Market'ı çöz ve OTOMATİK ÖDEME YAP - Herkese ayrı ayrı
-    pub fn resolve_and_payout_market(
-        env: Env,
-        admin: Address,
-        market_id: u64,
-    ) -> Result<PayoutSummary, Error> {
-        admin.require_auth();
+    /// Arbiter itirazı karara bağlar: bozulursa teminat iade edilir ve sonuç düzeltilir,
+    /// reddedilirse teminat hazineye aktarılır. Her iki durumda ödemeler açılır.
+    pub fn resolve_dispute(env: Env, arbiter: Address, market_id: u64, overturn: bool) -> Result<u32, Error> {
+        Self::require_role(&env, &arbiter, Role::Arbiter)?;
+        
+        let dispute_key = (DISPUTES, market_id);
+        let mut dispute: Dispute = env.storage().persistent()
+            .get(&dispute_key)
+            .ok_or(Error::DisputeNotFound)?;
+        
+        if dispute.is_settled {
+            return Err(Error::DisputeNotFound);
+        }
         
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-            
-        let mut market = markets.get(market_id)
-            .ok_or(Error::MarketNotFound)?;
+        let mut market = Self::load_market(&env, market_id)?;
         
-        if market.is_resolved {
-            return Err(Error::MarketAlreadyResolved);
+        // Teminat her iki durumda da kilitten çıkar: challenger'a ya da hazineye
+        Self::adjust_locked(&env, &market.betting_token, -dispute.bond);
+        if overturn {
+            // Sonuç değişince yuvarlama artığı da değişir
+            let previous_dust = Self::rounding_dust(&env, &market)?;
+            market.winning_outcome = Some(dispute.proposed_outcome);
+            market.final_price = Some(dispute.evidence_price);
+            Self::adjust_locked(&env, &market.betting_token, previous_dust - Self::rounding_dust(&env, &market)?);
+            
+            if dispute.bond > 0 {
+                let token_client = token::Client::new(&env, &market.betting_token);
+                token_client.transfer(&env.current_contract_address(), &dispute.challenger, &dispute.bond);
+            }
+        } else if dispute.bond > 0 {
+            let treasury_key = (TREASURY, market.betting_token.clone());
+            let accrued: i128 = env.storage().persistent().get(&treasury_key).unwrap_or(0);
+            env.storage().persistent().set(&treasury_key, &(accrued + dispute.bond));
         }
         
-        if market.is_paid_out {
-            return Err(Error::MarketAlreadyPaidOut);
+        market.dispute_deadline = env.ledger().timestamp();
+        market.transition(env.ledger().timestamp(), MarketStatus::Resolved)?;
+        if overturn {
+            // Düzeltilen sonuç Reflector'ın end_time kaydına dayanır
+            events::market_resolved(&env, &market, true, dispute.evidence_timestamp);
         }
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
+        Self::save_market(&env, &market);
         
-        let current_price = Self::get_price(env.clone(), market.token.clone());
-        let initial_price = market.initial_price;
+        dispute.is_settled = true;
+        dispute.overturned = overturn;
+        env.storage().persistent().set(&dispute_key, &dispute);
         
-        // Kazanan tarafı belirle
-        let price_change_percent = ((current_price - initial_price) * 10000) / initial_price;
+        events::dispute_settled(&env, market_id, &dispute, winning_outcome);
         
-        let winning_side = if price_change_percent > market.stable_tolerance {
-            0u32 // Up
-        } else if price_change_percent < -market.stable_tolerance {
-            1u32 // Down
-        } else {
-            2u32 // Stable
-        };
+        Ok(winning_outcome)
+    }
+
+    /// Market'ı iptal et - her pozisyon sahibi stake'ini komisyonsuz geri alır (claim_refund)
+    pub fn void_market(env: Env, resolver: Address, market_id: u64, reason: VoidReason) -> Result<(), Error> {
+        Self::require_role(&env, &resolver, Role::Resolver)?;
         
-        market.winning_side = Some(winning_side);
-        market.final_price = Some(current_price);
-        market.is_resolved = true;
+        if reason == VoidReason::None {
+            return Err(Error::InvalidVoidReason);
+        }
         
-        // **OTOMATİK ÖDEME SİSTEMİ**: Tüm kazananlara otomatik ödeme yap
-        let payout_summary = Self::process_payouts_improved(env.clone(), admin.clone(), market_id)?;
+        let mut market = Self::load_market(&env, market_id)?;
         
-        market.is_paid_out = true;
-        markets.set(market_id, market.clone());
-        env.storage().persistent().set(&MARKETS, &markets);
+        // Çözülmüş market'ta komisyon ayrılmış ve ödemeler başlamış olabilir
+        market.transition(env.ledger().timestamp(), MarketStatus::Voided)?;
         
-        // Otomatik yeniden başlatma
-        if market.auto_restart {
-            Self::create_market(
-                env,
-                admin,
-                market.title,
-                market.token,
-                market.betting_token,
-                current_price,
-                market.stable_tolerance,
-                market.restart_duration,
-                true,
-                market.house_edge,
-            )?;
+        // Cash-out'larda havuzda kalan fark hiçbir açık pozisyona ait değil: hazineye
+        if market.cash_out_surplus > 0 {
+            let treasury_key = (TREASURY, market.betting_token.clone());
+            let accrued: i128 = env.storage().persistent().get(&treasury_key).unwrap_or(0);
+            env.storage().persistent().set(&treasury_key, &(accrued + market.cash_out_surplus));
+            Self::adjust_locked(&env, &market.betting_token, -market.cash_out_surplus);
+            market.cash_out_surplus = 0;
         }
         
-        Ok(payout_summary)
+        market.void_reason = reason;
+        events::market_voided(&env, &market);
+        Self::save_market(&env, &market);
+        
+        // Açık satış ilanları geçersiz
+        env.storage().persistent().remove(&(LISTINGS, market_id));
+        
+        Ok(())
     }
 
-    /// **ÖNEMLİ: Otomatik ödeme işlemi**
-    fn process_payouts(
-        env: Env, 
-        market_id: u64, 
-        winning_side: u32 // 0=Up, 1=Down, 2=Stable
-    ) -> Result<PayoutSummary, Error> {
-        let markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-            
-        let market = markets.get(market_id)
+    /// İptal edilen market'taki tüm pozisyonların stake'ini iade al
+    pub fn claim_refund(env: Env, user: Address, market_id: u64) -> Result<i128, Error> {
+        user.require_auth();
+        
+        let market = Self::get_market(env.clone(), market_id)
             .ok_or(Error::MarketNotFound)?;
         
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
+        if !market.is_voided() {
+            return Err(Error::NotAuthorized);
+        }
+        
+        let mut refund = 0i128;
+        for mut position in Self::get_user_positions(env.clone(), market_id, user.clone()).iter() {
+            if position.is_paid_out {
+                continue;
+            }
+            refund += position.amount;
+            let amount = position.amount;
+            Self::mark_paid(&env, &mut position, amount, true);
+        }
+        
+        if refund == 0 {
+            return Err(Error::PositionNotFound);
+        }
         
         let token_client = token::Client::new(&env, &market.betting_token);
+        token_client.transfer(&env.current_contract_address(), &user, &refund);
+        Self::adjust_locked(&env, &market.betting_token, -refund);
+        Self::record_market_result(&env, &market, &user);
         
-        let mut total_winners = 0u32;
-        let mut total_paid_amount = 0i128;
+        Ok(refund)
+    }
+
+    /// Market'taki pozisyonların stake toplamı
+    fn position_stakes(env: &Env, market: &Market) -> i128 {
+        let market_users = Self::market_users(env, market.id);
         
-        // Tüm bahisleri kontrol et ve kazananlara ödeme yap
-        let market_counter: u64 = env.storage().persistent().get(&MARKET_COUNTER).unwrap_or(0);
+        let mut total = 0i128;
+        for user in market_users.iter() {
+            for position in Self::get_user_positions(env.clone(), market.id, user).iter() {
+                total += position.amount;
+            }
+        }
+        total
+    }
+
+    /// Pari-mutuel markette çözülen sonuca göre hiçbir pozisyona düşmeyen yuvarlama artığı:
+    /// ödül havuzundan tüm kazanç / iade ve tohum paylarının (ödenmiş olsun olmasın) farkı
+    fn rounding_dust(env: &Env, market: &Market) -> Result<i128, Error> {
+        if !market.is_pari_mutuel() {
+            return Ok(0);
+        }
         
-        for i in 1..=market_counter {
-            for j in 0..1000 { // Maksimum 1000 kullanıcı kontrolü (gas limiti için)
-                let user_bet_key = (symbol_short!("UBET"), market_id, j);
-                if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, u64), UserBet>(&user_bet_key) {
-                    if user_bet.prediction == winning_side && !user_bet.is_paid_out {
-                        // Kazanç hesapla
-                        let winnings = (user_bet.amount * user_bet.odds_when_placed) / 1000;
-                        
-                        // Token transferi - kontrat'tan kullanıcıya
-                        match token_client.try_transfer(&env.current_contract_address(), &user_bet.user, &winnings) {
-                            Ok(_) => {
-                                user_bet.winnings = winnings;
-                                user_bet.is_paid_out = true;
-                                env.storage().persistent().set(&user_bet_key, &user_bet);
-                                
-                                total_winners += 1;
-                                total_paid_amount += winnings;
-                            },
-                            Err(_) => {
-                                return Err(Error::PaymentFailed);
-                            }
-                        }
-                    }
-                }
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
+        let refunding = market.outcome_total(winning_outcome) == 0;
+        
+        let mut obligations = payout::liquidity_payout(market)?;
+        for user in Self::market_users(env, market.id).iter() {
+            for position in Self::get_user_positions(env.clone(), market.id, user).iter() {
+                obligations += if refunding {
+                    payout::refund_payout(market, position.amount)?
+                } else if position.outcome == winning_outcome {
+                    payout::winning_payout(market, &position)?
+                } else {
+                    0
+                };
             }
         }
+        Ok(payout::prize_pool(market)? - obligations)
+    }
+
+    /// Market'ı çöz ve OTOMATİK ÖDEME YAP - Herkese ayrı ayrı
+    pub fn resolve_and_payout_market(
+        env: Env,
+        admin: Address,
+        market_id: u64,
+    ) -> Result<PayoutSummary, Error> {
+        Self::require_role(&env, &admin, Role::Resolver)?;
+        
+        let mut market = Self::load_market(&env, market_id)?;
+        
+        if market.is_resolved() {
+            return Err(Error::MarketAlreadyResolved);
+        }
         
-        // Kalan komisyonu admin'e gönder (opsiyonel)
-        // token_client.transfer(&env.current_contract_address(), &admin, &house_commission);
+        let current_price = Self::get_settlement_price(&env, &market)
+            .ok_or(Error::OraclePriceUnavailable)?;
         
-        Ok(PayoutSummary {
-            total_winners,
-            total_paid_amount,
-            house_commission,
-            remaining_balance: total_volume - total_paid_amount - house_commission,
-        })
+        // Kazanan tarafı belirle (kimse işlem yapmadıysa açılış fiyatı şimdi alınır)
+        Self::snapshot_initial_price(&env, &mut market);
+        let winning_outcome = Self::determine_winning_outcome(&market, current_price.price)?;
+        
+        Self::settle_market(&env, &mut market, winning_outcome, &current_price, true)?;
+
+        // Ödeme fonksiyonu market'ı storage'dan okuduğu için önce kaydet
+        Self::save_market(&env, &market);
+
+        // **OTOMATİK ÖDEME SİSTEMİ**: Tüm kazananlara otomatik ödeme yap
+        // İtiraz süresi varsa ödemeler süre dolunca process_payouts_improved ile yapılır
+        let payout_summary = if Self::require_payable(&env, &market).is_ok() {
+            Self::internal_process_payouts(env.clone(), market_id)?
+        } else {
+            PayoutSummary {
+                total_winners: 0,
+                total_paid_amount: 0,
+                house_commission: payout::house_commission(market.total_volume(), market.house_edge)?,
+                remaining_balance: payout::winners_pool(&market)?,
+            }
+        };
+        
+        Self::continue_after_resolution(&env, &market, current_price.price);
+        
+        Ok(payout_summary)
     }
 
     /// ADİL MANUEL ÖDEME ALMA - Pool-Based Fair Claim
@@ -524,87 +2874,66 @@ impl PredictionMarket {
         let market = Self::get_market(env.clone(), market_id)
             .ok_or(Error::MarketNotFound)?;
         
-        if !market.is_resolved {
-            return Err(Error::MarketNotStarted);
-        }
-        
-        let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-        let mut user_bet: UserBet = env.storage().persistent()
-            .get(&user_bet_key)
-            .ok_or(Error::MarketNotFound)?;
-        
-        if user_bet.is_paid_out {
-            return Err(Error::UserAlreadyPaidOut);
-        }
-        
-        // Kazandı mı kontrol et
-        let winning_side = market.winning_side.ok_or(Error::MarketNotStarted)?;
-        
-        if user_bet.prediction != winning_side {
-            return Ok(0); // Kaybetti
-        }
+        Self::require_payable(&env, &market)?;
         
-        // **YENİ ADİL SİSTEM**: Gerçek ödeme hesaplama
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
         
-        let winning_pool = match winning_side {
-            0 => market.total_up_bets,
-            1 => market.total_down_bets,
-            2 => market.total_stable_bets,
-            _ => return Err(Error::InvalidPrediction),
+        // Kazanan outcome'daki pozisyon - yoksa kaybetti
+        let user_bet_key = Self::user_bet_key(market_id, &user, winning_outcome);
+        let mut user_bet: UserBet = match env.storage().persistent().get(&user_bet_key) {
+            Some(bet) => bet,
+            None => {
+                if Self::get_user_positions(env.clone(), market_id, user.clone()).is_empty() {
+                    return Err(Error::MarketNotFound);
+                }
+                Self::record_market_result(&env, &market, &user);
+                return Ok(0); // Kaybetti
+            },
         };
         
-        if winning_pool == 0 {
-            return Ok(0);
+        if user_bet.is_paid_out {
+            return Err(Error::UserAlreadyPaidOut);
         }
         
-        // Adil ödeme hesaplama
-        let user_share_of_winning_pool = (user_bet.amount * 1000) / winning_pool;
-        let fair_winnings = (prize_pool * user_share_of_winning_pool) / 1000;
-        let final_winnings = fair_winnings.max(user_bet.amount); // Minimum garanti
+        // **ADİL SİSTEM**: Tam hassasiyetli pro-rata ödeme (payout modülü)
+        let final_winnings = payout::winning_payout(&market, &user_bet)?;
         
         // Token transferi
         let token_client = token::Client::new(&env, &market.betting_token);
         token_client.transfer(&env.current_contract_address(), &user, &final_winnings);
+        Self::adjust_locked(&env, &market.betting_token, -final_winnings);
         
-        user_bet.winnings = final_winnings;
-        user_bet.is_paid_out = true;
-        env.storage().persistent().set(&user_bet_key, &user_bet);
+        Self::mark_paid(&env, &mut user_bet, final_winnings, false);
+        Self::record_market_result(&env, &market, &user);
         
         Ok(final_winnings)
     }
 
     /// Gelişmiş market istatistikleri
     pub fn get_market_stats(env: Env, market_id: u64) -> Result<MarketStats, Error> {
-        let markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-            
-        let market = markets.get(market_id)
-            .ok_or(Error::MarketNotFound)?;
-        
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let total_betters = market.up_betters_count + market.down_betters_count + market.stable_betters_count;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
-        
-        let (up_pct, down_pct, stable_pct) = if total_volume > 0 {
-            (
-                (market.total_up_bets * 10000) / total_volume,
-                (market.total_down_bets * 10000) / total_volume,
-                (market.total_stable_bets * 10000) / total_volume,
-            )
-        } else {
-            (0, 0, 0)
-        };
+        let market = Self::load_market(&env, market_id)?;
+        
+        let total_volume = market.total_volume();
+        let total_betters = market.total_betters();
+        let house_commission = payout::house_commission(total_volume, market.house_edge)?;
+        let prize_pool = payout::prize_pool(&market)?;
+        
+        let mut outcome_percentages = Vec::new(&env);
+        for (index, outcome) in market.outcomes.iter().enumerate() {
+            if let PricingMode::Lmsr(liquidity) = market.pricing {
+                // LMSR'de yüzde = AMM'nin olasılık fiyatı
+                outcome_percentages.push_back(lmsr::price_bps(&market, liquidity, index as u32)?);
+            } else if total_volume > 0 {
+                outcome_percentages.push_back((outcome.total_bets * 10000) / total_volume);
+            } else {
+                outcome_percentages.push_back(0);
+            }
+        }
         
         Ok(MarketStats {
             total_volume,
             total_betters,
-            up_percentage: up_pct,
-            down_percentage: down_pct,
-            stable_percentage: stable_pct,
+            outcome_percentages,
             prize_pool,
             house_commission,
         })
@@ -618,331 +2947,364 @@ impl PredictionMarket {
             None => return 0,
         };
         
-        if !market.is_resolved {
+        if !market.is_resolved() {
             return 0;
         }
         
-        let user_bet_key = (symbol_short!("UBET"), market_id, user);
-        let user_bet: UserBet = match env.storage().persistent().get(&user_bet_key) {
-            Some(bet) => bet,
-            None => return 0,
-        };
-        
         // Kazanan taraf kontrolü
-        let winning_side = match market.winning_side {
+        let winning_outcome = match market.winning_outcome {
             Some(side) => side,
             None => return 0,
         };
         
-        if user_bet.prediction != winning_side {
-            return 0; // Kaybetti
-        }
-        
-        // **YENİ ADİL SİSTEM**: Gerçek ödeme hesaplama
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
-        
-        // Kazanan pool'u belirle
-        let winning_pool = match winning_side {
-            0 => market.total_up_bets,    // Up
-            1 => market.total_down_bets,  // Down
-            2 => market.total_stable_bets, // Stable
-            _ => return 0,
+        // Kazanan outcome'daki toplam pozisyon
+        let user_bet_key = Self::user_bet_key(market_id, &user, winning_outcome);
+        let user_bet: UserBet = match env.storage().persistent().get(&user_bet_key) {
+            Some(bet) => bet,
+            None => return 0, // Kaybetti
         };
         
-        if winning_pool == 0 {
-            return 0;
-        }
-        
         // Adil payout hesaplama: Kullanıcının payı kadar ödül havuzundan pay
         // Formül: (Kullanıcının bahsi / Toplam kazanan bahisleri) * Ödül havuzu
-        let user_share_of_winning_pool = (user_bet.amount * 1000) / winning_pool; // 1000x precision
-        let fair_winnings = (prize_pool * user_share_of_winning_pool) / 1000;
-        
-        // Minimum garanti: En azından yatırdığını geri alsın
-        fair_winnings.max(user_bet.amount)
+        payout::winning_payout(&market, &user_bet).unwrap_or(0)
     }
 
     // Diğer fonksiyonlar aynı kalıyor...
     pub fn get_market(env: Env, market_id: u64) -> Option<Market> {
-        let markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-        markets.get(market_id)
+        let mut market: Market = env.storage().persistent().get(&(MARKETS, market_id))?;
+        market.status = market.status_at(env.ledger().timestamp());
+        Some(market)
+    }
+
+    /// Market kaydını saklanan durumuyla oku - güncel durum için `status_at`
+    fn load_market(env: &Env, market_id: u64) -> Result<Market, Error> {
+        env.storage().persistent()
+            .get(&(MARKETS, market_id))
+            .ok_or(Error::MarketNotFound)
+    }
+
+    fn save_market(env: &Env, market: &Market) {
+        env.storage().persistent().set(&(MARKETS, market.id), market);
+    }
+
+    pub fn get_user_bet(env: Env, market_id: u64, user: Address, outcome: u32) -> Option<UserBet> {
+        let user_bet_key = Self::user_bet_key(market_id, &user, outcome);
+        env.storage().persistent().get(&user_bet_key)
+    }
+
+    /// Kullanıcının market'taki tüm outcome pozisyonları
+    pub fn get_user_positions(env: Env, market_id: u64, user: Address) -> Vec<UserBet> {
+        let mut positions = Vec::new(&env);
+        let outcome_count = match Self::get_market(env.clone(), market_id) {
+            Some(market) => market.outcomes.len(),
+            None => return positions,
+        };
+        
+        for outcome in 0..outcome_count {
+            let user_bet_key = Self::user_bet_key(market_id, &user, outcome);
+            if let Some(bet) = env.storage().persistent().get::<(Symbol, u64, Address, u32), UserBet>(&user_bet_key) {
+                positions.push_back(bet);
+            }
+        }
+        positions
+    }
+
+    pub fn get_total_markets(env: Env) -> u64 {
+        env.storage().persistent().get(&MARKET_COUNTER).unwrap_or(0u64)
+    }
+
+    /// Aktif marketleri listele - `get_markets(MarketFilter::Status(Open))` ile aynı sayfalama
+    pub fn get_active_markets(env: Env, cursor: u64, limit: u32) -> MarketPage {
+        Self::get_markets(env, MarketFilter::Status(MarketStatus::Open), cursor, limit)
+    }
+
+    /// Filtreye uyan marketler, id sırasıyla `cursor`'dan başlayarak (0 = baştan).
+    /// `limit` en fazla MAX_PAGE_SIZE; sayfa başına en fazla MAX_PAGE_SCAN market okunur,
+    /// sayfa dolmasa da `next_cursor` kalan aralığı gösterir.
+    pub fn get_markets(env: Env, filter: MarketFilter, cursor: u64, limit: u32) -> MarketPage {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let last_id = Self::get_total_markets(env.clone());
+        
+        let mut page = Vec::new(&env);
+        let mut market_id = cursor.max(1);
+        let scan_end = market_id.saturating_add(MAX_PAGE_SCAN);
+        while market_id <= last_id {
+            if page.len() == limit || market_id == scan_end {
+                return MarketPage { markets: page, next_cursor: Some(market_id) };
+            }
+            if let Some(market) = Self::get_market(env.clone(), market_id) {
+                let matches = match &filter {
+                    MarketFilter::All => true,
+                    MarketFilter::Status(status) => market.status == *status,
+                    MarketFilter::Token(token) => market.betting_token == *token,
+                    MarketFilter::Creator(creator) => market.creator == *creator,
+                    MarketFilter::Series(series_id) => market.series_id == Some(*series_id),
+                };
+                if matches {
+                    page.push_back(market);
+                }
+            }
+            market_id += 1;
+        }
+        MarketPage { markets: page, next_cursor: None }
+    }
+
+    /// Market'taki tüm pozisyonlar, kullanıcı sırası üzerinden sayfalı - sayfa başına yalnızca
+    /// `limit` kullanıcının kaydı okunur
+    pub fn get_market_bets(env: Env, market_id: u64, cursor: u32, limit: u32) -> BetPage {
+        let count = Self::market_user_count(&env, market_id);
+        let end = cursor.saturating_add(limit.clamp(1, MAX_PAGE_SIZE)).min(count);
+        
+        let mut bets = Vec::new(&env);
+        for index in cursor..end {
+            if let Some(user) = Self::market_user_at(&env, market_id, index) {
+                bets.append(&Self::get_user_positions(env.clone(), market_id, user));
+            }
+        }
+        BetPage {
+            bets,
+            next_cursor: if end < count { Some(end) } else { None },
+        }
+    }
+
+    /// Kullanıcı sırasında `cursor`'dan başlayan en fazla MAX_USER_SCAN kullanıcı içinde en çok
+    /// stake'i olan `limit` kullanıcı, büyükten küçüğe. Tüm market için istemci sayfaları birleştirir.
+    pub fn get_top_betters(env: Env, market_id: u64, cursor: u32, limit: u32) -> BetterPage {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let count = Self::market_user_count(&env, market_id);
+        let end = cursor.saturating_add(MAX_USER_SCAN).min(count);
+        let mut top: Vec<BetterStake> = Vec::new(&env);
+        
+        for index in cursor..end {
+            let user = match Self::market_user_at(&env, market_id, index) {
+                Some(user) => user,
+                None => continue,
+            };
+            let mut staked = 0i128;
+            for position in Self::get_user_positions(env.clone(), market_id, user.clone()).iter() {
+                staked += position.amount;
+            }
+            
+            let index = top.iter().position(|better| better.staked < staked).unwrap_or(top.len() as usize);
+            if (index as u32) < limit {
+                top.insert(index as u32, BetterStake { user, staked });
+                if top.len() > limit {
+                    top.pop_back();
+                }
+            }
+        }
+        BetterPage {
+            betters: top,
+            next_cursor: if end < count { Some(end) } else { None },
+        }
     }
 
-    pub fn get_user_bet(env: Env, market_id: u64, user: Address) -> Option<UserBet> {
-        let user_bet_key = (symbol_short!("UBET"), market_id, user);
-        env.storage().persistent().get(&user_bet_key)
+    /// Kullanıcının pozisyon aldığı marketler, en yeni önce (`cursor` = atlanacak kayıt sayısı)
+    pub fn get_user_history(env: Env, user: Address, cursor: u32, limit: u32) -> UserHistoryPage {
+        let user_markets: Vec<u64> = env.storage().persistent()
+            .get(&(USER_MARKETS, user.clone())).unwrap_or(Vec::new(&env));
+        let end = cursor.saturating_add(limit.clamp(1, MAX_PAGE_SIZE)).min(user_markets.len());
+        
+        let mut records = Vec::new(&env);
+        for i in cursor..end {
+            let market_id = user_markets.get_unchecked(user_markets.len() - 1 - i);
+            let market = match Self::get_market(env.clone(), market_id) {
+                Some(market) => market,
+                None => continue,
+            };
+            let positions = Self::get_user_positions(env.clone(), market_id, user.clone());
+            let (staked, returned): (i128, i128) = env.storage().persistent()
+                .get(&(USER_FLOWS, user.clone(), market_id)).unwrap_or((0, 0));
+            
+            let has_open_position = positions.iter().any(|position| Self::is_position_open(&market, &position));
+            records.push_back(UserMarketHistory {
+                market_id,
+                status: market.status,
+                winning_outcome: market.winning_outcome,
+                positions,
+                staked,
+                returned,
+                realized_pnl: if has_open_position { None } else { Some(returned - staked) },
+            });
+        }
+        UserHistoryPage {
+            records,
+            next_cursor: if end < user_markets.len() { Some(end) } else { None },
+        }
     }
 
-    pub fn get_total_markets(env: Env) -> u64 {
-        env.storage().persistent().get(&MARKET_COUNTER).unwrap_or(0u64)
+    /// Kullanıcının token'daki ömür boyu istatistikleri
+    pub fn get_user_stats(env: Env, user: Address, token: Address) -> UserStats {
+        env.storage().persistent()
+            .get(&(USER_STATS, user, token))
+            .unwrap_or(UserStats::empty())
     }
 
-    /// Aktif marketleri listele
-    pub fn get_active_markets(env: Env) -> Vec<Market> {
-        let markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-        
-        let mut active_markets = Vec::new(&env);
-        let current_time = env.ledger().timestamp();
-        
-        for market in markets.values() {
-            if !market.is_resolved && 
-               market.start_time <= current_time && 
-               market.end_time > current_time {
-                active_markets.push_back(market);
-            }
-        }
-        
-        active_markets
+    pub fn get_season(env: Env) -> Season {
+        env.storage().persistent()
+            .get(&SEASON)
+            .unwrap_or(Season { id: 1, started_at: 0 })
     }
 
-    /// Kontrat bakiyesi kontrolü
-    pub fn get_contract_balance(env: Env, token: Address) -> i128 {
-        let token_client = token::Client::new(&env, &token);
-        token_client.balance(&env.current_contract_address())
+    /// Sezonun token'daki ilk `limit` kullanıcısı - puana, eşitlikte net kâra göre
+    pub fn get_leaderboard(env: Env, season: u32, token: Address, limit: u32) -> Vec<LeaderboardEntry> {
+        let top: Vec<LeaderboardEntry> = env.storage().persistent()
+            .get(&(SEASON_TOP, season, token)).unwrap_or(Vec::new(&env));
+        top.slice(0..limit.clamp(1, LEADERBOARD_SIZE).min(top.len()))
     }
-
-    /// Test bahis koy - Token transferi olmadan (u32 prediction ile)
-    pub fn place_bet_test_u32(
-        env: Env,
-        user: Address,
-        market_id: u64,
-        amount: i128,
-        prediction: u32, // 0=Up, 1=Down, 2=Stable
-    ) -> Result<(), Error> {
-        user.require_auth();
-        
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-        
-        // Prediction validation
-        if prediction > 2 {
-            return Err(Error::InvalidPrediction);
-        }
-        
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-            
-        let mut market = markets.get(market_id)
-            .ok_or(Error::MarketNotFound)?;
-        
-        let current_time = env.ledger().timestamp();
-        
-        if current_time < market.start_time {
-            return Err(Error::MarketNotStarted);
+    
+    /// Kullanıcının yeni sezon kaydını sıralı tabloya yerleştir.
+    /// Her yeni market puanı artırdığından sıralama anahtarı neredeyse hep yükselir; tablodan düşen
+    /// kullanıcı yeni bir sonuç kaydedilmeden geri giremez, bu yüzden ilk N'i tutmak yeterli.
+    /// Anahtar yalnızca aynı markete yeniden girenin ek kaydında düşebilir: dolu tablonun sonuna
+    /// düşen kullanıcıyı tablo dışındakiler geçmiş olabileceğinden tablodan çıkar.
+    fn update_leaderboard(env: &Env, season: u32, token: &Address, user: &Address, stats: &UserStats) {
+        let top_key = (SEASON_TOP, season, token.clone());
+        let mut top: Vec<LeaderboardEntry> = env.storage().persistent()
+            .get(&top_key).unwrap_or(Vec::new(env));
+        let was_full = top.len() >= LEADERBOARD_SIZE;
+        
+        let rank = (stats.points, stats.net_pnl);
+        let mut dropped = false;
+        if let Some(index) = top.iter().position(|entry| entry.user == *user) {
+            let previous = top.get_unchecked(index as u32).stats;
+            dropped = rank < (previous.points, previous.net_pnl);
+            top.remove(index as u32);
         }
         
-        if current_time > market.end_time {
-            return Err(Error::MarketExpired);
+        let index = top.iter()
+            .position(|entry| (entry.stats.points, entry.stats.net_pnl) < rank)
+            .unwrap_or(top.len() as usize) as u32;
+        if index >= LEADERBOARD_SIZE {
+            return;
         }
-        
-        if market.is_resolved {
-            return Err(Error::MarketAlreadyResolved);
+        if dropped && was_full && index == top.len() {
+            env.storage().persistent().set(&top_key, &top);
+            return;
         }
-        
-        let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-        if env.storage().persistent().has(&user_bet_key) {
-            return Err(Error::UserAlreadyBet);
+        top.insert(index, LeaderboardEntry { user: user.clone(), stats: stats.clone() });
+        if top.len() > LEADERBOARD_SIZE {
+            top.pop_back();
         }
+        env.storage().persistent().set(&top_key, &top);
+    }
+
+    /// Yeni sezonu başlat - önceki sezonların tabloları sorgulanabilir kalır
+    pub fn start_new_season(env: Env, admin: Address) -> Result<Season, Error> {
+        Self::require_admin(&env, &admin)?;
         
-        // Token transferi YOK - sadece test için
-        
-        // Kullanıcıyı market kullanıcıları listesine ekle
-        let market_users_key = (MARKET_USERS, market_id);
-        let mut market_users: Vec<Address> = env.storage().persistent()
-            .get(&market_users_key).unwrap_or(Vec::new(&env));
-        market_users.push_back(user.clone());
-        env.storage().persistent().set(&market_users_key, &market_users);
-        
-        // Mevcut oranları hesapla
-        let current_odds = Self::calculate_improved_odds(env.clone(), market_id)?;
-        let odds_when_placed = match prediction {
-            0 => current_odds.up_odds,    // Up
-            1 => current_odds.down_odds,  // Down
-            2 => current_odds.stable_odds, // Stable
-            _ => return Err(Error::InvalidPrediction),
+        let season = Season {
+            id: Self::get_season(env.clone()).id + 1,
+            started_at: env.ledger().timestamp(),
         };
+        env.storage().persistent().set(&SEASON, &season);
         
-        // Bahis kaydet
-        let bet = UserBet {
-            user: user.clone(),
-            market_id,
-            amount,
-            prediction,
-            timestamp: current_time,
-            odds_when_placed,
-            is_paid_out: false,
-            winnings: 0,
+        events::season_started(&env, &season);
+        Ok(season)
+    }
+
+    /// Kullanıcının çözülmüş ya da iptal edilmiş marketteki sonucunu ömür boyu ve sezon
+    /// istatistiklerine bir kez işle. Kazanan pozisyon (iadede tüm pozisyonlar) ödenene kadar
+    /// sonuç kesin değildir - sonraki claim / ödeme turu işler.
+    fn record_market_result(env: &Env, market: &Market, user: &Address) {
+        // İptalde ve kazanansız pari-mutuel markette herkes iade alır, kazanan yoktur
+        let refunding = match market.winning_outcome {
+            Some(outcome) => market.is_pari_mutuel() && market.outcome_total(outcome) == 0,
+            None if market.is_voided() => true,
+            None => return,
         };
-        
-        env.storage().persistent().set(&user_bet_key, &bet);
-        
-        // Market güncelle
-        match prediction {
-            0 => { // Up
-                market.total_up_bets += amount;
-                market.up_betters_count += 1;
-            },
-            1 => { // Down
-                market.total_down_bets += amount;
-                market.down_betters_count += 1;
-            },
-            2 => { // Stable
-                market.total_stable_bets += amount;
-                market.stable_betters_count += 1;
-            },
-            _ => return Err(Error::InvalidPrediction),
+        let positions = Self::get_user_positions(env.clone(), market.id, user.clone());
+        let pays_out = |position: &UserBet| refunding || Some(position.outcome) == market.winning_outcome;
+        if positions.iter().any(|position| !position.is_paid_out && pays_out(&position)) {
+            return;
         }
         
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
-        
-        Ok(())
+        let won = !refunding && positions.iter().any(|position| pays_out(&position));
+        Self::apply_market_result(env, market, user, won);
     }
 
-    /// Test bahis koy - Token transferi olmadan
-    pub fn place_bet_test(
-        env: Env,
-        user: Address,
-        market_id: u64,
-        amount: i128,
-        prediction: u32, // 0=Up, 1=Down, 2=Stable
-    ) -> Result<(), Error> {
-        user.require_auth();
-        
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-        
-        // Prediction validation
-        if prediction > 2 {
-            return Err(Error::InvalidPrediction);
-        }
-        
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-            
-        let mut market = markets.get(market_id)
-            .ok_or(Error::MarketNotFound)?;
-        
-        let current_time = env.ledger().timestamp();
-        
-        if current_time < market.start_time {
-            return Err(Error::MarketNotStarted);
-        }
-        
-        if current_time > market.end_time {
-            return Err(Error::MarketExpired);
+    /// Kullanıcının market akışlarından henüz işlenmemiş kısmını ömür boyu ve sezon
+    /// istatistiklerine ekle - market yalnızca ilk kayıtta oynanmış sayılır
+    fn apply_market_result(env: &Env, market: &Market, user: &Address, won: bool) {
+        let recorded_key = (STATS_RECORDED, user.clone(), market.id);
+        let recorded: Option<(i128, i128)> = env.storage().persistent().get(&recorded_key);
+        let (recorded_staked, recorded_returned) = recorded.unwrap_or((0, 0));
+        
+        let (total_staked, total_returned): (i128, i128) = env.storage().persistent()
+            .get(&(USER_FLOWS, user.clone(), market.id)).unwrap_or((0, 0));
+        let staked = total_staked - recorded_staked;
+        let returned = total_returned - recorded_returned;
+        if staked == 0 && returned == 0 {
+            return;
         }
-        
-        if market.is_resolved {
-            return Err(Error::MarketAlreadyResolved);
-        }
-        
-        let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-        if env.storage().persistent().has(&user_bet_key) {
-            return Err(Error::UserAlreadyBet);
+        env.storage().persistent().set(&recorded_key, &(total_staked, total_returned));
+        
+        let new_market = recorded.is_none();
+        let token = market.betting_token.clone();
+        let mut lifetime = Self::get_user_stats(env.clone(), user.clone(), token.clone());
+        lifetime.record(staked, returned, won, new_market);
+        env.storage().persistent().set(&(USER_STATS, user.clone(), token.clone()), &lifetime);
+        
+        let season = Self::get_season(env.clone()).id;
+        let season_key = (SEASON_STATS, season, token.clone(), user.clone());
+        let mut season_stats: UserStats = env.storage().persistent()
+            .get(&season_key).unwrap_or(UserStats::empty());
+        season_stats.record(staked, returned, won, new_market);
+        env.storage().persistent().set(&season_key, &season_stats);
+        Self::update_leaderboard(env, season, &token, user, &season_stats);
+    }
+
+    /// Pozisyon henüz sonuçlanmadı mı: ödenmemiş kazanç / iade ya da çözülmemiş market
+    fn is_position_open(market: &Market, position: &UserBet) -> bool {
+        if position.is_paid_out {
+            return false;
         }
-        
-        // Token transferi YOK - sadece test için
-        
-        // Kullanıcıyı market kullanıcıları listesine ekle
-        let market_users_key = (MARKET_USERS, market_id);
-        let mut market_users: Vec<Address> = env.storage().persistent()
-            .get(&market_users_key).unwrap_or(Vec::new(&env));
-        market_users.push_back(user.clone());
-        env.storage().persistent().set(&market_users_key, &market_users);
-        
-        // Mevcut oranları hesapla
-        let current_odds = Self::calculate_improved_odds(env.clone(), market_id)?;
-        let odds_when_placed = match prediction {
-            0 => current_odds.up_odds,    // Up
-            1 => current_odds.down_odds,  // Down
-            2 => current_odds.stable_odds, // Stable
-            _ => return Err(Error::InvalidPrediction),
-        };
-        
-        // Bahis kaydet
-        let bet = UserBet {
-            user: user.clone(),
-            market_id,
-            amount,
-            prediction,
-            timestamp: current_time,
-            odds_when_placed,
-            is_paid_out: false,
-            winnings: 0,
-        };
-        
-        env.storage().persistent().set(&user_bet_key, &bet);
-        
-        // Market güncelle
-        match prediction {
-            0 => { // Up
-                market.total_up_bets += amount;
-                market.up_betters_count += 1;
-            },
-            1 => { // Down
-                market.total_down_bets += amount;
-                market.down_betters_count += 1;
+        match (market.status, market.winning_outcome) {
+            (MarketStatus::Resolved | MarketStatus::Settled, Some(winning_outcome)) => {
+                // Kazanansız pari-mutuel markette tüm pozisyonlar iade bekler
+                position.outcome == winning_outcome
+                    || (market.is_pari_mutuel() && market.outcome_total(winning_outcome) == 0)
             },
-            2 => { // Stable
-                market.total_stable_bets += amount;
-                market.stable_betters_count += 1;
-            },
-            _ => return Err(Error::InvalidPrediction),
+            _ => true,
         }
-        
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
-        
-        Ok(())
+    }
+
+    /// Kontrat bakiyesi kontrolü
+    pub fn get_contract_balance(env: Env, token: Address) -> i128 {
+        let token_client = token::Client::new(&env, &token);
+        token_client.balance(&env.current_contract_address())
     }
 
     /// Manuel test için market çözme fonksiyonu + OTOMATİK ÖDEME
     pub fn resolve_market_manual(env: Env, admin: Address, market_id: u64, final_price: i128) -> Result<u32, Error> {
-        admin.require_auth();
-        
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
+        Self::require_role(&env, &admin, Role::Resolver)?;
         
-        let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
+        let mut market = Self::load_market(&env, market_id)?;
         
-        if market.is_resolved {
+        if market.is_resolved() {
             return Err(Error::MarketAlreadyResolved);
         }
         
-        market.final_price = Some(final_price);
+        let final_price = Self::get_manual_settlement_price(&env, &market, final_price)?;
         
-        // Kazanan tarafı belirle
-        let winning_side = if final_price > market.target_price + market.stable_tolerance {
-            0u32 // Up
-        } else if final_price < market.target_price - market.stable_tolerance {
-            1u32 // Down
-        } else {
-            2u32 // Stable
-        };
+        // Kazanan tarafı belirle (kimse işlem yapmadıysa açılış fiyatı şimdi alınır)
+        Self::snapshot_initial_price(&env, &mut market);
+        let winning_outcome = Self::determine_winning_outcome(&market, final_price.price)?;
         
-        market.winning_side = Some(winning_side);
-        market.is_resolved = true;
+        let oracle_settled = market.settlement_mode != SettlementMode::Spot;
+        Self::settle_market(&env, &mut market, winning_outcome, &final_price, oracle_settled)?;
         
-        markets.set(market_id, market.clone());
-        env.storage().persistent().set(&MARKETS, &markets);
+        Self::save_market(&env, &market);
         
         // **OTOMATİK ÖDEME SİSTEMİ**: Market çözüldükten hemen sonra tüm kazananlara ödeme yap
-        match Self::auto_transfer_winnings(env.clone(), market_id, winning_side) {
+        match Self::auto_transfer_winnings(env.clone(), market_id, winning_outcome) {
             Ok(_) => {
                 // Ödeme başarılı - market'ı paid_out olarak işaretle
-                let mut updated_markets: Map<u64, Market> = env.storage().persistent()
-                    .get(&MARKETS).unwrap_or(Map::new(&env));
-                if let Some(mut updated_market) = updated_markets.get(market_id) {
-                    updated_market.is_paid_out = true;
-                    updated_markets.set(market_id, updated_market);
-                    env.storage().persistent().set(&MARKETS, &updated_markets);
+                let mut updated_market = Self::load_market(&env, market_id)?;
+                // Kazanan yoksa iade yolu market'ı zaten kapatmış olur
+                if !updated_market.is_paid_out() {
+                    updated_market.transition(env.ledger().timestamp(), MarketStatus::Settled)?;
+                    Self::save_market(&env, &updated_market);
                 }
             },
             Err(_) => {
@@ -950,92 +3312,72 @@ impl PredictionMarket {
             }
         }
         
-        Ok(winning_side)
+        Self::continue_after_resolution(&env, &market, final_price.price);
+        
+        Ok(winning_outcome)
     }
 
     /// Manuel market çözme (sadece resolve, ödeme yok)
     pub fn resolve_market_manual_only(env: Env, admin: Address, market_id: u64, final_price: i128) -> Result<u32, Error> {
-        admin.require_auth();
+        Self::require_role(&env, &admin, Role::Resolver)?;
         
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
+        let mut market = Self::load_market(&env, market_id)?;
         
-        let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
-        
-        if market.is_resolved {
+        if market.is_resolved() {
             return Err(Error::MarketAlreadyResolved);
         }
         
-        market.final_price = Some(final_price);
+        let final_price = Self::get_manual_settlement_price(&env, &market, final_price)?;
         
-        // Kazanan tarafı belirle
-        let winning_side = if final_price > market.target_price + market.stable_tolerance {
-            0u32 // Up
-        } else if final_price < market.target_price - market.stable_tolerance {
-            1u32 // Down
-        } else {
-            2u32 // Stable
-        };
+        // Kazanan tarafı belirle (kimse işlem yapmadıysa açılış fiyatı şimdi alınır)
+        Self::snapshot_initial_price(&env, &mut market);
+        let winning_outcome = Self::determine_winning_outcome(&market, final_price.price)?;
+        
+        let oracle_settled = market.settlement_mode != SettlementMode::Spot;
+        Self::settle_market(&env, &mut market, winning_outcome, &final_price, oracle_settled)?;
         
-        market.winning_side = Some(winning_side);
-        market.is_resolved = true;
+        Self::save_market(&env, &market);
         
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
+        Self::continue_after_resolution(&env, &market, final_price.price);
         
-        Ok(winning_side)
+        Ok(winning_outcome)
     }
 
     /// Düzeltilmiş payout hesaplaması
     pub fn process_payouts_manual(env: Env, admin: Address, market_id: u64) -> Result<PayoutSummary, Error> {
-        admin.require_auth();
-        
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
+        Self::require_role(&env, &admin, Role::Resolver)?;
         
-        let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
+        let mut market = Self::load_market(&env, market_id)?;
         
-        if !market.is_resolved {
-            return Err(Error::MarketNotStarted);
+        // Kazanan havuzu boşsa claim edecek kimse yok - Settled'a geçmeden önce herkese iade et
+        if market.is_pari_mutuel() && market.winning_outcome.is_some_and(|o| market.outcome_total(o) == 0) {
+            return Self::internal_refund_no_winners(env, market_id);
         }
         
-        if market.is_paid_out {
-            return Err(Error::MarketAlreadyPaidOut);
-        }
+        // Resolved değilse (itiraz süresi, açık itiraz, iptal) neden ödenemediğini döner
+        market.transition(env.ledger().timestamp(), MarketStatus::Settled)?;
         
-        let winning_side = market.winning_side.as_ref().ok_or(Error::MarketNotStarted)?;
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
         
         // Düzeltilmiş payout hesaplaması
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool_after_commission = total_volume - house_commission;
-        
-        let winning_pool = match winning_side {
-            0 => market.total_up_bets,    // Up
-            1 => market.total_down_bets,  // Down
-            2 => market.total_stable_bets, // Stable
-            _ => return Err(Error::InvalidPrediction),
-        };
+        let total_volume = market.total_volume();
+        let house_commission = payout::house_commission(total_volume, market.house_edge)?;
+        let prize_pool_after_commission = payout::winners_pool(&market)?;
         
-        let winning_count = match winning_side {
-            0 => market.up_betters_count,    // Up
-            1 => market.down_betters_count,  // Down
-            2 => market.stable_betters_count, // Stable
-            _ => return Err(Error::InvalidPrediction),
-        };
+        let winning_pool = market.outcome_total(winning_outcome);
+        
+        let winning_count = market.outcome_betters(winning_outcome);
         
         let total_actual_payouts = if winning_count > 0 && winning_pool > 0 {
-            // Effective multiplier ile toplam gerçek ödeme hesapla
-            let effective_multiplier = (prize_pool_after_commission * 1000) / winning_pool;
-            (winning_pool * effective_multiplier) / 1000
+            // Kazanan havuzun tamamı ödül havuzunu alır
+            payout::pro_rata(winning_pool, winning_pool, prize_pool_after_commission)?
         } else {
             0
         };
         
-        market.is_paid_out = true;
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
+        Self::save_market(&env, &market);
         
+        // Transfer yok - kazananlar claim_winnings ile alır, SETTLED ödeme yapan turlara ait
         Ok(PayoutSummary {
             total_winners: winning_count,
             total_paid_amount: total_actual_payouts,
@@ -1044,145 +3386,65 @@ impl PredictionMarket {
         })
     }
 
-    /// ADİL TEST ÖDEME ALMA - Pool-Based Fair Test Claim  
-    pub fn claim_winnings_test(env: Env, user: Address, market_id: u64) -> Result<i128, Error> {
-        user.require_auth();
-        
-        let market = Self::get_market(env.clone(), market_id)
-            .ok_or(Error::MarketNotFound)?;
-        
-        if !market.is_resolved {
-            return Err(Error::MarketNotStarted);
-        }
-        
-        let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-        let mut user_bet: UserBet = env.storage().persistent()
-            .get(&user_bet_key)
-            .ok_or(Error::MarketNotFound)?;
-        
-        if user_bet.is_paid_out {
-            return Err(Error::UserAlreadyPaidOut);
-        }
-        
-        // Kazandı mı kontrol et
-        let winning_side = market.winning_side.ok_or(Error::MarketNotStarted)?;
-        
-        if user_bet.prediction != winning_side {
-            return Ok(0); // Kaybetti
-        }
-        
-        // **YENİ ADİL SİSTEM**: Gerçek ödeme hesaplama
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
-        
-        let winning_pool = match winning_side {
-            0 => market.total_up_bets,
-            1 => market.total_down_bets,
-            2 => market.total_stable_bets,
-            _ => return Err(Error::InvalidPrediction),
-        };
-        
-        if winning_pool == 0 {
-            return Ok(0);
-        }
-        
-        // Adil ödeme hesaplama
-        let user_share_of_winning_pool = (user_bet.amount * 1000) / winning_pool;
-        let fair_winnings = (prize_pool * user_share_of_winning_pool) / 1000;
-        let final_winnings = fair_winnings.max(user_bet.amount); // Minimum garanti
-        
-        // Token transferi YOK - sadece test için
-        user_bet.winnings = final_winnings;
-        user_bet.is_paid_out = true;
-        env.storage().persistent().set(&user_bet_key, &user_bet);
-        
-        Ok(final_winnings)
-    }
-
     /// OTOMATİK ADİL ÖDEME SİSTEMİ - Gerçek token transferi ile
     /// Bu fonksiyon market çözüldükten sonra otomatik olarak tüm kazananlara ödeme yapar
-    pub fn process_payouts_improved(env: Env, _admin: Address, market_id: u64) -> Result<PayoutSummary, Error> {
-        // Auth check kaldırıldı - çünkü bu fonksiyon resolve_market_manual içinden çağrılıyor
-        
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-        
-        let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
+    pub fn process_payouts_improved(env: Env, admin: Address, market_id: u64) -> Result<PayoutSummary, Error> {
+        Self::require_role(&env, &admin, Role::Resolver)?;
+        Self::internal_process_payouts(env, market_id)
+    }
+
+    /// Toplu ödeme turu - yetki kontrolü çağıranda (resolve_and_payout_market içinden de çağrılır)
+    fn internal_process_payouts(env: Env, market_id: u64) -> Result<PayoutSummary, Error> {
+        let mut market = Self::load_market(&env, market_id)?;
         
-        if !market.is_resolved {
-            return Err(Error::MarketNotStarted);
+        // Kazanan havuzu boşsa kimseye ödeme düşmez - auto_transfer_winnings gibi iadeye yönlendir
+        if market.is_pari_mutuel() && market.winning_outcome.is_some_and(|o| market.outcome_total(o) == 0) {
+            return Self::internal_refund_no_winners(env, market_id);
         }
         
-        if market.is_paid_out {
-            return Err(Error::MarketAlreadyPaidOut);
-        }
+        // Resolved değilse (itiraz süresi, açık itiraz, iptal) neden ödenemediğini döner
+        market.transition(env.ledger().timestamp(), MarketStatus::Settled)?;
         
-        let winning_side = market.winning_side.ok_or(Error::MarketNotStarted)?;
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
         
         // **ADİL SİSTEM**: Komisyon sonrası ödül havuzu hesaplama
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
-        
-        let winning_pool = match winning_side {
-            0 => market.total_up_bets,    // Up
-            1 => market.total_down_bets,  // Down
-            2 => market.total_stable_bets, // Stable
-            _ => return Err(Error::InvalidPrediction),
-        };
+        let total_volume = market.total_volume();
+        let house_commission = payout::house_commission(total_volume, market.house_edge)?;
+        let prize_pool = payout::winners_pool(&market)?;
         
-        let winning_count = match winning_side {
-            0 => market.up_betters_count,    // Up
-            1 => market.down_betters_count,  // Down
-            2 => market.stable_betters_count, // Stable
-            _ => return Err(Error::InvalidPrediction),
-        };
+        let winning_pool = market.outcome_total(winning_outcome);
+        
+        let winning_count = market.outcome_betters(winning_outcome);
         
         let mut total_actual_payouts = 0i128;
         let mut actual_winners = 0u32;
         
         if winning_count > 0 && winning_pool > 0 {
-            // Market kullanıcıları listesini al
-            let market_users_key = (MARKET_USERS, market_id);
-            let market_users: Vec<Address> = env.storage().persistent()
-                .get(&market_users_key).unwrap_or(Vec::new(&env));
+            let market_users = Self::market_users(&env, market_id);
             
             // Token client
             let token_client = token::Client::new(&env, &market.betting_token);
             
             // **ADİL ÖDEME**: Her kazanan kullanıcıya payı kadar ödeme yap
             for user in market_users.iter() {
-                let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-                if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address), UserBet>(&user_bet_key) {
-                    if user_bet.prediction == winning_side && !user_bet.is_paid_out {
+                let user_bet_key = Self::user_bet_key(market_id, &user, winning_outcome);
+                if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address, u32), UserBet>(&user_bet_key) {
+                    if !user_bet.is_paid_out {
                         // Adil ödeme hesaplama
-                        let user_share_of_winning_pool = (user_bet.amount * 1000) / winning_pool;
-                        let fair_winnings = (prize_pool * user_share_of_winning_pool) / 1000;
-                        let final_winnings = fair_winnings.max(user_bet.amount); // Minimum garanti
+                        let final_winnings = payout::winning_payout(&market, &user_bet)?;
                         
                         // Token transferi yap
                         match token_client.try_transfer(&env.current_contract_address(), &user, &final_winnings) {
                             Ok(_) => {
-                                user_bet.winnings = final_winnings;
-                                user_bet.is_paid_out = true;
-                                env.storage().persistent().set(&user_bet_key, &user_bet);
+                                Self::adjust_locked(&env, &market.betting_token, -final_winnings);
+                                Self::mark_paid(&env, &mut user_bet, final_winnings, false);
                                 
                                 total_actual_payouts += final_winnings;
                                 actual_winners += 1;
-                                
-                                // Event yayınla
-                                env.events().publish(
-                                    (symbol_short!("PAYOUT"),), 
-                                    (user.clone(), final_winnings)
-                                );
                             },
                             Err(_) => {
                                 // Token transfer başarısız - log et ama devam et
-                                env.events().publish(
-                                    (symbol_short!("PAY_FAIL"),), 
-                                    (user.clone(), final_winnings)
-                                );
+                                events::payout_failed(&env, &user_bet, final_winnings);
                                 continue;
                             }
                         }
@@ -1191,226 +3453,41 @@ impl PredictionMarket {
             }
         }
         
-        market.is_paid_out = true;
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
+        for user in Self::market_users(&env, market_id).iter() {
+            Self::record_market_result(&env, &market, &user);
+        }
         
-        // Summary event
-        env.events().publish(
-            (symbol_short!("PAY_DONE"),), 
-            (market_id, actual_winners, total_actual_payouts)
-        );
+        Self::return_liquidity(&env, &mut market)?;
+        Self::save_market(&env, &market);
         
-        Ok(PayoutSummary {
+        let summary = PayoutSummary {
             total_winners: actual_winners,
             total_paid_amount: total_actual_payouts,
             house_commission,
-            remaining_balance: total_volume - total_actual_payouts - house_commission,
-        })
-    }
-
-    /// Test için otomatik payout (token transfer olmadan)
-    // pub fn process_payouts_improved_test(env: Env, admin: Address, market_id: u64) -> Result<PayoutSummary, Error> {
-    //     admin.require_auth();
-        
-    //     let mut markets: Map<u64, Market> = env.storage().persistent()
-    //         .get(&MARKETS).unwrap_or(Map::new(&env));
-        
-    //     let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
-        
-    //     if !market.is_resolved {
-    //         return Err(Error::MarketNotStarted);
-    //     }
-        
-    //     if market.is_paid_out {
-    //         return Err(Error::MarketAlreadyPaidOut);
-    //     }
-        
-    //     let winning_side = market.winning_side.as_ref().ok_or(Error::MarketNotStarted)?;
-        
-    //     // Hesaplamalar
-    //     let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-    //     let house_commission = (total_volume * market.house_edge) / 10000;
-    //     let prize_pool_after_commission = total_volume - house_commission;
-        
-    //     let winning_pool = match winning_side {
-    //         PredictionType::Up => market.total_up_bets,
-    //         PredictionType::Down => market.total_down_bets,
-    //         PredictionType::Stable => market.total_stable_bets,
-    //     };
-        
-    //     let winning_count = match winning_side {
-    //         PredictionType::Up => market.up_betters_count,
-    //         PredictionType::Down => market.down_betters_count,
-    //         PredictionType::Stable => market.stable_betters_count,
-    //     };
-        
-    //     let mut total_actual_payouts = 0i128;
-    //     let mut actual_winners = 0u32;
-        
-    //     if winning_count > 0 && winning_pool > 0 {
-    //         let effective_multiplier = (prize_pool_after_commission * 1000) / winning_pool;
-            
-    //         // Market kullanıcıları listesini al
-    //         let market_users_key = (MARKET_USERS, market_id);
-    //         let market_users: Vec<Address> = env.storage().persistent()
-    //             .get(&market_users_key).unwrap_or(Vec::new(&env));
-            
-    //         // Her kullanıcıyı kontrol et
-    //         for user in market_users.iter() {
-    //             let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-    //             if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address), UserBet>(&user_bet_key) {
-    //                 if user_bet.prediction == *winning_side && !user_bet.is_paid_out {
-    //                     let winnings = (user_bet.amount * effective_multiplier) / 1000;
-                        
-    //                     // Token transferi YOK - sadece test için
-    //                     user_bet.winnings = winnings;
-    //                     user_bet.is_paid_out = true;
-    //                     env.storage().persistent().set(&user_bet_key, &user_bet);
-                        
-    //                     total_actual_payouts += winnings;
-    //                     actual_winners += 1;
-    //                 }
-    //             }
-    //         }
-    //     }
-        
-    //     market.is_paid_out = true;
-    //     markets.set(market_id, market);
-    //     env.storage().persistent().set(&MARKETS, &markets);
-        
-    //     Ok(PayoutSummary {
-    //         total_winners: actual_winners,
-    //         total_paid_amount: total_actual_payouts,
-    //         house_commission,
-    //         remaining_balance: prize_pool_after_commission - total_actual_payouts,
-    //     })
-    // }
-
-    /// Test için otomatik payout (token transfer olmadan) - DÜZELTİLMİŞ
-pub fn process_payouts_improved_test(env: Env, admin: Address, market_id: u64) -> Result<PayoutSummary, Error> {
-    admin.require_auth();
-    
-    let mut markets: Map<u64, Market> = env.storage().persistent()
-        .get(&MARKETS).unwrap_or(Map::new(&env));
-    
-    let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
-    
-    if !market.is_resolved {
-        return Err(Error::MarketNotStarted);
-    }
-    
-    if market.is_paid_out {
-        return Err(Error::MarketAlreadyPaidOut);
-    }
-    
-    let winning_side = market.winning_side.as_ref().ok_or(Error::MarketNotStarted)?;
-    
-    // Hesaplamalar
-    let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-    let house_commission = (total_volume * market.house_edge) / 10000;
-    let prize_pool_after_commission = total_volume - house_commission;
-    
-    let winning_pool = match winning_side {
-        0 => market.total_up_bets,    // Up
-        1 => market.total_down_bets,  // Down
-        2 => market.total_stable_bets, // Stable
-        _ => return Err(Error::InvalidPrediction),
-    };
-    
-    let winning_count = match winning_side {
-        0 => market.up_betters_count,    // Up
-        1 => market.down_betters_count,  // Down
-        2 => market.stable_betters_count, // Stable
-        _ => return Err(Error::InvalidPrediction),
-    };
-    
-    let mut total_actual_payouts = 0i128;
-    let mut actual_winners = 0u32;
-    
-    if winning_count > 0 && winning_pool > 0 {
-        let effective_multiplier = (prize_pool_after_commission * 1000) / winning_pool;
-        
-        // Market kullanıcıları listesini al
-        let market_users_key = (MARKET_USERS, market_id);
-        let market_users: Vec<Address> = env.storage().persistent()
-            .get(&market_users_key).unwrap_or(Vec::new(&env));
-        
-        // Her kullanıcıyı kontrol et
-        for user in market_users.iter() {
-            let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-            if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address), UserBet>(&user_bet_key) {
-                if user_bet.prediction == *winning_side && !user_bet.is_paid_out {
-                    let winnings = (user_bet.amount * effective_multiplier) / 1000;
-                    
-                    // Token transferi YOK - sadece test için
-                    user_bet.winnings = winnings;
-                    user_bet.is_paid_out = true;
-                    env.storage().persistent().set(&user_bet_key, &user_bet);
-                    
-                    total_actual_payouts += winnings;
-                    actual_winners += 1;
-                }
-            }
-        }
+            remaining_balance: prize_pool - total_actual_payouts,
+        };
+        events::settled(&env, market_id, &summary);
+        Ok(summary)
     }
-    
-    market.is_paid_out = true;
-    markets.set(market_id, market);
-    env.storage().persistent().set(&MARKETS, &markets);
-    
-    // DÜZELTİLMİŞ KISIM: Doğru remaining_balance hesaplaması
-    let remaining_balance = total_volume - total_actual_payouts - house_commission;
-    
-    // Debug için - isteğe bağlı
-    env.events().publish(
-        (symbol_short!("PAYOUT"),), 
-        (
-            symbol_short!("total_vol"), total_volume,
-            symbol_short!("paid"), total_actual_payouts,
-            symbol_short!("comm"), house_commission,
-            symbol_short!("remain"), remaining_balance
-        )
-    );
-    
-    Ok(PayoutSummary {
-        total_winners: actual_winners,
-        total_paid_amount: total_actual_payouts,
-        house_commission,
-        remaining_balance, // Artık doğru hesaplanıyor
-    })
-}
 
     /// OTOMATİK TRANSFER SİSTEMİ - claim_winnings mantığıyla tüm kazananlara otomatik ödeme
     /// Bu fonksiyon market çözüldükten sonra otomatik olarak tüm kazananlara claim_winnings mantığıyla ödeme yapar
-    pub fn auto_transfer_winnings(env: Env, market_id: u64, winning_side: u32) -> Result<PayoutSummary, Error> {
+    /// (sadece resolve_market_manual içinden; yetki kontrolü orada)
+    fn auto_transfer_winnings(env: Env, market_id: u64, winning_outcome: u32) -> Result<PayoutSummary, Error> {
         let market = Self::get_market(env.clone(), market_id)
             .ok_or(Error::MarketNotFound)?;
         
-        if !market.is_resolved {
-            return Err(Error::MarketNotStarted);
-        }
+        Self::require_payable(&env, &market)?;
         
         // **ADİL SİSTEM**: claim_winnings ile aynı hesaplama
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let prize_pool = total_volume - house_commission;
-        
-        let winning_pool = match winning_side {
-            0 => market.total_up_bets,
-            1 => market.total_down_bets,
-            2 => market.total_stable_bets,
-            _ => return Err(Error::InvalidPrediction),
-        };
+        let total_volume = market.total_volume();
+        let house_commission = payout::house_commission(total_volume, market.house_edge)?;
+        let prize_pool = payout::winners_pool(&market)?;
         
-        // 🔄 AUTO REFUND: Eğer kazanan pool 0 ise, otomatik iade yap
-        if winning_pool == 0 {
-            // Auto-refund sistemini çağır
-            env.events().publish(
-                (symbol_short!("NO_WIN"),), 
-                (symbol_short!("REFUND"), market_id, total_volume)
-            );
-            
+        let winning_pool = market.outcome_total(winning_outcome);
+        
+        // 🔄 AUTO REFUND: Eğer kazanan pool 0 ise, otomatik iade yap (sabit oranda stake'ler bankroll'a kalır)
+        if winning_pool == 0 && market.is_pari_mutuel() {
             // Internal refund (admin auth olmadan)
             return Self::internal_refund_no_winners(env, market_id);
         }
@@ -1418,46 +3495,31 @@ pub fn process_payouts_improved_test(env: Env, admin: Address, market_id: u64) -
         let mut total_actual_payouts = 0i128;
         let mut actual_winners = 0u32;
         
-        // Market kullanıcıları listesini al
-        let market_users_key = (MARKET_USERS, market_id);
-        let market_users: Vec<Address> = env.storage().persistent()
-            .get(&market_users_key).unwrap_or(Vec::new(&env));
+        let market_users = Self::market_users(&env, market_id);
         
         // Token client
         let token_client = token::Client::new(&env, &market.betting_token);
         
         // **ADİL TRANSFER**: Her kazanan kullanıcıya claim_winnings mantığıyla ödeme yap
         for user in market_users.iter() {
-            let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-            if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address), UserBet>(&user_bet_key) {
-                if user_bet.prediction == winning_side && !user_bet.is_paid_out {
+            let user_bet_key = Self::user_bet_key(market_id, &user, winning_outcome);
+            if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address, u32), UserBet>(&user_bet_key) {
+                if !user_bet.is_paid_out {
                     // claim_winnings ile aynı hesaplama
-                    let user_share_of_winning_pool = (user_bet.amount * 1000) / winning_pool;
-                    let fair_winnings = (prize_pool * user_share_of_winning_pool) / 1000;
-                    let final_winnings = fair_winnings.max(user_bet.amount); // Minimum garanti
+                    let final_winnings = payout::winning_payout(&market, &user_bet)?;
                     
                     // Token transferi yap
                     match token_client.try_transfer(&env.current_contract_address(), &user, &final_winnings) {
                         Ok(_) => {
-                            user_bet.winnings = final_winnings;
-                            user_bet.is_paid_out = true;
-                            env.storage().persistent().set(&user_bet_key, &user_bet);
+                            Self::adjust_locked(&env, &market.betting_token, -final_winnings);
+                            Self::mark_paid(&env, &mut user_bet, final_winnings, false);
                             
                             total_actual_payouts += final_winnings;
                             actual_winners += 1;
-                            
-                            // Başarı event'i
-                            env.events().publish(
-                                (symbol_short!("AUTO_PAY"),), 
-                                (user.clone(), final_winnings)
-                            );
                         },
                         Err(_) => {
                             // Token transfer başarısız - log et ama devam et
-                            env.events().publish(
-                                (symbol_short!("AUTO_FAIL"),), 
-                                (user.clone(), final_winnings)
-                            );
+                            events::payout_failed(&env, &user_bet, final_winnings);
                             continue;
                         }
                     }
@@ -1465,18 +3527,25 @@ pub fn process_payouts_improved_test(env: Env, admin: Address, market_id: u64) -
             }
         }
         
-        // Summary event
-        env.events().publish(
-            (symbol_short!("AUTO_DONE"),), 
-            (market_id, actual_winners, total_actual_payouts)
-        );
+        // Kaybedenler dahil herkesin sonucu - ödemesi başarısız kazananlar sonraki claim'de
+        for user in market_users.iter() {
+            Self::record_market_result(&env, &market, &user);
+        }
         
-        Ok(PayoutSummary {
+        if !market.liquidity.is_empty() && !market.liquidity_returned {
+            let mut market = market;
+            Self::return_liquidity(&env, &mut market)?;
+            Self::save_market(&env, &market);
+        }
+        
+        let summary = PayoutSummary {
             total_winners: actual_winners,
             total_paid_amount: total_actual_payouts,
             house_commission,
-            remaining_balance: total_volume - total_actual_payouts - house_commission,
-        })
+            remaining_balance: prize_pool - total_actual_payouts,
+        };
+        events::settled(&env, market_id, &summary);
+        Ok(summary)
     }
 
     /// 🔄 AUTO REFUND SYSTEM - Kazanan yoksa tüm bahisleri iade et
@@ -1486,88 +3555,70 @@ pub fn process_payouts_improved_test(env: Env, admin: Address, market_id: u64) -
         admin: Address,
         market_id: u64
     ) -> Result<PayoutSummary, Error> {
-        admin.require_auth();
-        
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
+        Self::require_role(&env, &admin, Role::Resolver)?;
         
-        let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
+        let mut market = Self::load_market(&env, market_id)?;
         
-        if !market.is_resolved {
-            return Err(Error::MarketNotStarted);
-        }
+        market.transition(env.ledger().timestamp(), MarketStatus::Settled)?;
         
-        if market.is_paid_out {
-            return Err(Error::MarketAlreadyPaidOut);
+        // Sabit oranlı marketlerde kazanan yoksa stake'ler bankroll'a kalır
+        if !market.is_pari_mutuel() {
+            return Err(Error::UnsupportedPricingMode);
         }
         
-        // Kazanan sayısını kontrol et
-        let winning_side = market.winning_side.unwrap_or(0);
-        let winner_count = match winning_side {
-            0 => market.up_betters_count,
-            1 => market.down_betters_count,
-            2 => market.stable_betters_count,
-            _ => 0,
-        };
-        
-        // Eğer kazanan varsa, refund yapma
-        if winner_count > 0 {
+        // Kazanan havuzu boş değilse (bahis ya da tohum likidite), refund yapma
+        let winning_outcome = market.winning_outcome.unwrap_or(0);
+        if market.outcome_total(winning_outcome) > 0 {
             return Err(Error::NotAuthorized); // Kazanan var, refund gerekmiyor
         }
         
         // 🔄 REFUND SİSTEMİ: Tüm bahisleri iade et (komisyon düşülerek)
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let refund_pool = total_volume - house_commission;
+        let total_volume = market.total_volume();
+        let house_commission = payout::house_commission(total_volume, market.house_edge)?;
+        let refund_pool = payout::prize_pool(&market)?;
         
         let token_client = token::Client::new(&env, &market.betting_token);
         
         let mut total_refunded = 0i128;
         let mut refunded_users = 0u32;
         
-        // Market kullanıcıları listesini al
-        let market_users_key = (MARKET_USERS, market_id);
-        let market_users: Vec<Address> = env.storage().persistent()
-            .get(&market_users_key).unwrap_or(Vec::new(&env));
+        let market_users = Self::market_users(&env, market_id);
         
         // Her kullanıcıya iade yap
         for user in market_users.iter() {
-            let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-            if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address), UserBet>(&user_bet_key) {
-                if !user_bet.is_paid_out && user_bet.amount > 0 {
-                    // Refund hesaplama: (User bet / Total volume) * Refund pool
-                    let user_share = (user_bet.amount * 1000) / total_volume; // 1000x precision
-                    let refund_amount = (refund_pool * user_share) / 1000;
+            // Kullanıcının tüm outcome pozisyonları
+            for outcome in 0..market.outcomes.len() {
+                let user_bet_key = Self::user_bet_key(market_id, &user, outcome);
+                if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address, u32), UserBet>(&user_bet_key) {
+                    if !user_bet.is_paid_out && user_bet.amount > 0 {
+                        // Refund hesaplama: (User bet / Total volume) * Refund pool
+                        let refund_amount = payout::pro_rata(user_bet.amount, total_volume, refund_pool)?;
                     
-                    // Token transferi
-                    token_client.transfer(&env.current_contract_address(), &user, &refund_amount);
+                        // Token transferi
+                        token_client.transfer(&env.current_contract_address(), &user, &refund_amount);
+                        Self::adjust_locked(&env, &market.betting_token, -refund_amount);
                     
-                    user_bet.winnings = refund_amount;
-                    user_bet.is_paid_out = true;
-                    env.storage().persistent().set(&user_bet_key, &user_bet);
+                        Self::mark_paid(&env, &mut user_bet, refund_amount, true);
                     
-                    total_refunded += refund_amount;
-                    refunded_users += 1;
+                        total_refunded += refund_amount;
+                        refunded_users += 1;
+                    }
                 }
             }
+            Self::record_market_result(&env, &market, &user);
         }
         
-        market.is_paid_out = true;
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
+        Self::return_liquidity(&env, &mut market)?;
+        Self::save_market(&env, &market);
         
-        // Event publish
-        env.events().publish(
-            (symbol_short!("REFUND"), market_id),
-            (refunded_users, total_refunded, house_commission)
-        );
-        
-        Ok(PayoutSummary {
+        let summary = PayoutSummary {
             total_winners: refunded_users,
             total_paid_amount: total_refunded,
             house_commission,
-            remaining_balance: total_volume - total_refunded - house_commission,
-        })
+            remaining_balance: refund_pool - total_refunded,
+        };
+        events::settled(&env, market_id, &summary);
+        Ok(summary)
     }
 
     /// 🔍 Check if market needs refund (no winners)
@@ -1577,89 +3628,67 @@ pub fn process_payouts_improved_test(env: Env, admin: Address, market_id: u64) -
             None => return false,
         };
         
-        if !market.is_resolved || market.is_paid_out {
+        if !market.is_resolved() || market.is_paid_out() || !market.is_pari_mutuel() {
             return false;
         }
         
-        // Kazanan sayısını kontrol et
-        let winning_side = market.winning_side.unwrap_or(0);
-        let winner_count = match winning_side {
-            0 => market.up_betters_count,
-            1 => market.down_betters_count,
-            2 => market.stable_betters_count,
-            _ => 0,
-        };
-        
-        winner_count == 0
+        // Kazanan havuzunu kontrol et (tohum likidite de kazanan sayılır)
+        let winning_outcome = market.winning_outcome.unwrap_or(0);
+        market.outcome_total(winning_outcome) == 0
     }
 
     /// 🔄 INTERNAL REFUND SYSTEM - Admin auth olmadan internal kullanım
     fn internal_refund_no_winners(env: Env, market_id: u64) -> Result<PayoutSummary, Error> {
-        let mut markets: Map<u64, Market> = env.storage().persistent()
-            .get(&MARKETS).unwrap_or(Map::new(&env));
-        
-        let mut market = markets.get(market_id).ok_or(Error::MarketNotFound)?;
+        let mut market = Self::load_market(&env, market_id)?;
         
-        if !market.is_resolved {
-            return Err(Error::MarketNotStarted);
-        }
-        
-        if market.is_paid_out {
-            return Err(Error::MarketAlreadyPaidOut);
-        }
+        market.transition(env.ledger().timestamp(), MarketStatus::Settled)?;
         
         // 🔄 REFUND SİSTEMİ: Tüm bahisleri iade et (komisyon düşülerek)
-        let total_volume = market.total_up_bets + market.total_down_bets + market.total_stable_bets;
-        let house_commission = (total_volume * market.house_edge) / 10000;
-        let refund_pool = total_volume - house_commission;
+        let total_volume = market.total_volume();
+        let house_commission = payout::house_commission(total_volume, market.house_edge)?;
+        let refund_pool = payout::prize_pool(&market)?;
         
         let token_client = token::Client::new(&env, &market.betting_token);
         
         let mut total_refunded = 0i128;
         let mut refunded_users = 0u32;
         
-        // Market kullanıcıları listesini al
-        let market_users_key = (MARKET_USERS, market_id);
-        let market_users: Vec<Address> = env.storage().persistent()
-            .get(&market_users_key).unwrap_or(Vec::new(&env));
+        let market_users = Self::market_users(&env, market_id);
         
         // Her kullanıcıya iade yap
         for user in market_users.iter() {
-            let user_bet_key = (symbol_short!("UBET"), market_id, user.clone());
-            if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address), UserBet>(&user_bet_key) {
-                if !user_bet.is_paid_out && user_bet.amount > 0 {
-                    // Refund hesaplama: (User bet / Total volume) * Refund pool
-                    let user_share = (user_bet.amount * 1000) / total_volume; // 1000x precision
-                    let refund_amount = (refund_pool * user_share) / 1000;
-                    
-                    // Token transferi
-                    token_client.transfer(&env.current_contract_address(), &user, &refund_amount);
+            // Kullanıcının tüm outcome pozisyonları
+            for outcome in 0..market.outcomes.len() {
+                let user_bet_key = Self::user_bet_key(market_id, &user, outcome);
+                if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address, u32), UserBet>(&user_bet_key) {
+                    if !user_bet.is_paid_out && user_bet.amount > 0 {
+                        // Refund hesaplama: (User bet / Total volume) * Refund pool
+                        let refund_amount = payout::pro_rata(user_bet.amount, total_volume, refund_pool)?;
                     
-                    user_bet.winnings = refund_amount;
-                    user_bet.is_paid_out = true;
-                    env.storage().persistent().set(&user_bet_key, &user_bet);
+                        // Token transferi
+                        token_client.transfer(&env.current_contract_address(), &user, &refund_amount);
+                        Self::adjust_locked(&env, &market.betting_token, -refund_amount);
                     
-                    total_refunded += refund_amount;
-                    refunded_users += 1;
+                        Self::mark_paid(&env, &mut user_bet, refund_amount, true);
                     
-                    // Refund event
-                    env.events().publish(
-                        (symbol_short!("REFUND"),), 
-                        (user.clone(), refund_amount)
-                    );
+                        total_refunded += refund_amount;
+                        refunded_users += 1;
+                    }
                 }
             }
+            Self::record_market_result(&env, &market, &user);
         }
         
-        market.is_paid_out = true;
-        markets.set(market_id, market);
-        env.storage().persistent().set(&MARKETS, &markets);
+        Self::return_liquidity(&env, &mut market)?;
+        Self::save_market(&env, &market);
         
-        Ok(PayoutSummary {
+        let summary = PayoutSummary {
             total_winners: refunded_users,
             total_paid_amount: total_refunded,
             house_commission,
-            remaining_balance: total_volume - total_refunded - house_commission,
-        })
+            remaining_balance: refund_pool - total_refunded,
+        };
+        events::settled(&env, market_id, &summary);
+        Ok(summary)
     }
 }
\ No newline at end of file
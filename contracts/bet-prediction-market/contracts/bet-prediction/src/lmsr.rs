@@ -0,0 +1,126 @@
+//! LMSR (logarithmic market scoring rule) fiyatlaması - `no_std` için sabit noktalı exp/ln.
+//!
+//! Maliyet fonksiyonu C(q) = b * ln(Σ exp(q_i / b)); `q_i` outcome'daki toplam pay (`liability`),
+//! `b` likidite parametresi. Pay almak C(q + Δ) - C(q) kadar tutar; creator'ın en fazla zararı
+//! b * ln(n) olduğundan market bu kadar bankroll ile fonlanmadan işlem açılmaz.
+//! Hesaplar log-sum-exp ile yapılır: üsler hep ≤ 0 olduğu için taşma olmaz.
+
+use crate::{Error, Market};
+
+pub const SCALE: i128 = 1_000_000_000_000_000_000; // 1e18 = 1.0
+const LN2: i128 = 693_147_180_559_945_309;
+
+/// e^x, x ≤ 0 (SCALE ölçekli)
+pub fn exp_neg(x: i128) -> Result<i128, Error> {
+    if x > 0 {
+        return Err(Error::CalculationError);
+    }
+    // e^x = 2^-k * e^r, r ∈ (-ln2, 0]
+    let k = (-x) / LN2;
+    if k > 64 {
+        return Ok(0);
+    }
+    let r = x + k * LN2;
+    
+    let mut term = SCALE;
+    let mut sum = SCALE;
+    let mut n = 1i128;
+    while term != 0 {
+        term = term * r / (n * SCALE);
+        sum += term;
+        n += 1;
+    }
+    Ok(sum >> k)
+}
+
+/// ln(x), x > 0 (SCALE ölçekli)
+pub fn ln(x: i128) -> Result<i128, Error> {
+    if x <= 0 {
+        return Err(Error::CalculationError);
+    }
+    // x = m * 2^k, m ∈ [1, 2)
+    let mut m = x;
+    let mut k = 0i128;
+    while m >= 2 * SCALE {
+        m >>= 1;
+        k += 1;
+    }
+    while m < SCALE {
+        m <<= 1;
+        k -= 1;
+    }
+    
+    // ln(m) = 2 * atanh(z), z = (m - 1) / (m + 1) ≤ 1/3
+    let z = (m - SCALE) * SCALE / (m + SCALE);
+    let z2 = z * z / SCALE;
+    let mut term = z;
+    let mut sum = 0i128;
+    let mut n = 1i128;
+    while term != 0 {
+        sum += term / n;
+        term = term * z2 / SCALE;
+        n += 2;
+    }
+    Ok(2 * sum + k * LN2)
+}
+
+/// `outcome`'a `delta` pay eklendikten sonraki C(q) (token cinsinden)
+fn cost_after(market: &Market, b: i128, outcome: u32, delta: i128) -> Result<i128, Error> {
+    let exponent = |i: u32, shares: i128| -> Result<i128, Error> {
+        let shares = if i == outcome { shares + delta } else { shares };
+        if shares < 0 {
+            return Err(Error::CalculationError);
+        }
+        shares.checked_mul(SCALE).map(|v| v / b).ok_or(Error::CalculationError)
+    };
+    
+    let mut max = 0i128;
+    for (i, pool) in market.outcomes.iter().enumerate() {
+        max = max.max(exponent(i as u32, pool.liability)?);
+    }
+    
+    let mut sum = 0i128;
+    for (i, pool) in market.outcomes.iter().enumerate() {
+        sum += exp_neg(exponent(i as u32, pool.liability)? - max)?;
+    }
+    
+    let log_sum_exp = max + ln(sum)?;
+    b.checked_mul(log_sum_exp).map(|v| v / SCALE).ok_or(Error::CalculationError)
+}
+
+/// `shares` pay almanın maliyeti - yuvarlama market maker lehine (yukarı)
+pub fn buy_cost(market: &Market, b: i128, outcome: u32, shares: i128) -> Result<i128, Error> {
+    let cost = cost_after(market, b, outcome, shares)? - cost_after(market, b, outcome, 0)?;
+    Ok(cost + 1)
+}
+
+/// `shares` pay satışının geliri - yuvarlama market maker lehine (aşağı)
+pub fn sell_proceeds(market: &Market, b: i128, outcome: u32, shares: i128) -> Result<i128, Error> {
+    let proceeds = cost_after(market, b, outcome, 0)? - cost_after(market, b, outcome, -shares)?;
+    Ok((proceeds - 1).max(0))
+}
+
+/// Outcome'un anlık fiyatı / olasılığı (basis points)
+pub fn price_bps(market: &Market, b: i128, outcome: u32) -> Result<i128, Error> {
+    let mut max = 0i128;
+    for pool in market.outcomes.iter() {
+        max = max.max(pool.liability * SCALE / b);
+    }
+    
+    let mut sum = 0i128;
+    let mut own = 0i128;
+    for (i, pool) in market.outcomes.iter().enumerate() {
+        let weight = exp_neg(pool.liability * SCALE / b - max)?;
+        if i as u32 == outcome {
+            own = weight;
+        }
+        sum += weight;
+    }
+    Ok(own * crate::payout::BPS_DENOMINATOR / sum)
+}
+
+/// Creator'ın en fazla zararı: ceil(b * ln(n))
+pub fn max_loss(b: i128, outcome_count: u32) -> Result<i128, Error> {
+    let ln_n = ln(outcome_count as i128 * SCALE)?;
+    b.checked_mul(ln_n).map(|v| v / SCALE + 1).ok_or(Error::CalculationError)
+}
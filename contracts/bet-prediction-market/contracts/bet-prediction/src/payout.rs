@@ -0,0 +1,111 @@
+//! Ödeme hesapları - tüm claim, otomatik ödeme ve iade yolları bu modülü kullanır.
+//!
+//! Pari-mutuel değişmezi: bir market için ödenen toplam + house komisyonu, toplam hacmi
+//! (cash-out artığı dahil) asla geçmez. Her ödeme aşağı yuvarlanır; yuvarlama artığı (dust)
+//! en fazla kazanan sayısı kadar birimdir ve kontratta house payı olarak kalır.
+//!
+//! Sabit oranlı marketlerde ödeme, bahis anında kilitlenen oranlarla hesaplanır ve
+//! kazanan outcome'un `liability` toplamını geçmez; karşılığı bahis anında bankroll'dan ayrılır.
+//! LMSR marketlerinde her kazanan pay 1 birim öder (bkz. `lmsr`).
+//!
+//! Pari-mutuel marketlerde creator'ın tohum likiditesi havuzlara dahildir ve kazanan outcome'daki
+//! tohum, bahisler gibi ödül havuzundan payını alır; kaybeden outcome'lardaki tohum kazananlara gider.
+
+use crate::{Error, Market, PricingMode, UserBet};
+
+pub const BPS_DENOMINATOR: i128 = 10_000;
+pub const ODDS_DENOMINATOR: i128 = 1000; // 1000 = 1.0x
+
+/// Toplam hacimden kesilen komisyon (aşağı yuvarlanır)
+pub fn house_commission(total_volume: i128, house_edge: i128) -> Result<i128, Error> {
+    if total_volume < 0 || !(0..=BPS_DENOMINATOR).contains(&house_edge) {
+        return Err(Error::CalculationError);
+    }
+    total_volume
+        .checked_mul(house_edge)
+        .map(|v| v / BPS_DENOMINATOR)
+        .ok_or(Error::CalculationError)
+}
+
+/// Kazananlara (ya da iadeye) dağıtılacak havuz: hacim - komisyon + cash-out artığı
+pub fn prize_pool(market: &Market) -> Result<i128, Error> {
+    let total_volume = market.total_volume();
+    let commission = house_commission(total_volume, market.house_edge)?;
+    Ok(total_volume - commission + market.cash_out_surplus)
+}
+
+/// Tam hassasiyetli oransal pay: floor(stake * pool / total_stake)
+pub fn pro_rata(stake: i128, total_stake: i128, pool: i128) -> Result<i128, Error> {
+    if stake < 0 || pool < 0 || stake > total_stake {
+        return Err(Error::CalculationError);
+    }
+    if total_stake == 0 {
+        return Ok(0);
+    }
+    stake
+        .checked_mul(pool)
+        .map(|v| v / total_stake)
+        .ok_or(Error::CalculationError)
+}
+
+/// Kilitli oranla yapılan bahsin kazanırsa ödemesi: floor(amount * odds / 1000)
+pub fn fixed_odds_payout(amount: i128, odds: i128) -> Result<i128, Error> {
+    if amount < 0 || odds < ODDS_DENOMINATOR {
+        return Err(Error::CalculationError);
+    }
+    amount
+        .checked_mul(odds)
+        .map(|v| v / ODDS_DENOMINATOR)
+        .ok_or(Error::CalculationError)
+}
+
+/// Kazanan outcome'a ödenecek toplam
+pub fn winners_pool(market: &Market) -> Result<i128, Error> {
+    match market.pricing {
+        PricingMode::PariMutuel => prize_pool(market),
+        PricingMode::FixedOdds(_) | PricingMode::Lmsr(_) => {
+            let winning_outcome = market.winning_outcome.ok_or(Error::CalculationError)?;
+            Ok(market.outcome_liability(winning_outcome))
+        },
+    }
+}
+
+/// Kazanan outcome'daki pozisyonun ödemesi
+pub fn winning_payout(market: &Market, position: &UserBet) -> Result<i128, Error> {
+    match market.pricing {
+        PricingMode::PariMutuel => {
+            let winning_outcome = market.winning_outcome.ok_or(Error::CalculationError)?;
+            let winning_pool = market.outcome_total(winning_outcome);
+            pro_rata(position.amount, winning_pool, prize_pool(market)?)
+        },
+        PricingMode::FixedOdds(_) => {
+            let mut total = 0i128;
+            for entry in position.entries.iter() {
+                total += fixed_odds_payout(entry.amount, entry.odds_when_placed)?;
+            }
+            Ok(total)
+        },
+        PricingMode::Lmsr(_) => Ok(position.shares),
+    }
+}
+
+/// Kazanan yoksa `stake` büyüklüğündeki pozisyonun iadesi
+pub fn refund_payout(market: &Market, stake: i128) -> Result<i128, Error> {
+    pro_rata(stake, market.total_volume(), prize_pool(market)?)
+}
+
+/// Creator'ın tohum likiditesinin geri dönüşü: iptalde tamamı, kazanan havuzu boşsa iade payı,
+/// aksi halde kazanan outcome'daki tohumun ödül havuzundaki payı
+pub fn liquidity_payout(market: &Market) -> Result<i128, Error> {
+    let seeded = market.liquidity_total();
+    if seeded == 0 || market.is_voided() {
+        return Ok(seeded);
+    }
+    let winning_outcome = market.winning_outcome.ok_or(Error::CalculationError)?;
+    let winning_pool = market.outcome_total(winning_outcome);
+    if winning_pool == 0 {
+        return refund_payout(market, seeded);
+    }
+    let winning_seed = market.liquidity.get(winning_outcome).unwrap_or(0);
+    pro_rata(winning_seed, winning_pool, prize_pool(market)?)
+}
@@ -0,0 +1,133 @@
+//! Token hareketi olmadan market state'ini değiştiren test entrypoint'leri.
+//! Sadece `testutils` feature'ı ile derlenir - deploy edilen kontratta bulunmaz.
+
+use soroban_sdk::{contractimpl, Address, Env, Symbol};
+
+use crate::{
+    events, payout, Error, MarketStatus, PayoutSummary, PredictionMarket, PredictionMarketArgs,
+    PredictionMarketClient, Role, UserBet,
+};
+
+#[contractimpl]
+impl PredictionMarket {
+    /// Test bahis koy - Token transferi olmadan (u32 outcome ile)
+    pub fn place_bet_test_u32(
+        env: Env,
+        user: Address,
+        market_id: u64,
+        amount: i128,
+        outcome: u32, // Market.outcomes içindeki index
+    ) -> Result<(), Error> {
+        user.require_auth();
+        
+        // Token transferi YOK - sadece test için
+        Self::record_bet(&env, &user, market_id, amount, outcome, false)
+    }
+
+    /// Test bahis koy - Token transferi olmadan
+    pub fn place_bet_test(
+        env: Env,
+        user: Address,
+        market_id: u64,
+        amount: i128,
+        outcome: u32, // Market.outcomes içindeki index
+    ) -> Result<(), Error> {
+        user.require_auth();
+        
+        // Token transferi YOK - sadece test için
+        Self::record_bet(&env, &user, market_id, amount, outcome, false)
+    }
+
+    /// ADİL TEST ÖDEME ALMA - Pool-Based Fair Test Claim  
+    pub fn claim_winnings_test(env: Env, user: Address, market_id: u64) -> Result<i128, Error> {
+        user.require_auth();
+        
+        let market = Self::get_market(env.clone(), market_id)
+            .ok_or(Error::MarketNotFound)?;
+        
+        Self::require_payable(&env, &market)?;
+        
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
+        
+        // Kazanan outcome'daki pozisyon - yoksa kaybetti
+        let user_bet_key = Self::user_bet_key(market_id, &user, winning_outcome);
+        let mut user_bet: UserBet = match env.storage().persistent().get(&user_bet_key) {
+            Some(bet) => bet,
+            None => {
+                if Self::get_user_positions(env.clone(), market_id, user.clone()).is_empty() {
+                    return Err(Error::MarketNotFound);
+                }
+                return Ok(0); // Kaybetti
+            },
+        };
+        
+        if user_bet.is_paid_out {
+            return Err(Error::UserAlreadyPaidOut);
+        }
+        
+        // **ADİL SİSTEM**: Tam hassasiyetli pro-rata ödeme (payout modülü)
+        let final_winnings = payout::winning_payout(&market, &user_bet)?;
+        
+        // Token transferi YOK - sadece test için
+        Self::mark_paid(&env, &mut user_bet, final_winnings, false);
+        
+        Ok(final_winnings)
+    }
+
+    /// Test için otomatik payout (token transfer olmadan) - DÜZELTİLMİŞ
+    pub fn process_payouts_improved_test(env: Env, admin: Address, market_id: u64) -> Result<PayoutSummary, Error> {
+        Self::require_role(&env, &admin, Role::Resolver)?;
+
+        let mut market = Self::load_market(&env, market_id)?;
+
+        market.transition(env.ledger().timestamp(), MarketStatus::Settled)?;
+
+        let winning_outcome = market.winning_outcome.ok_or(Error::MarketNotResolved)?;
+
+        // Hesaplamalar
+        let total_volume = market.total_volume();
+        let house_commission = payout::house_commission(total_volume, market.house_edge)?;
+        let prize_pool_after_commission = payout::winners_pool(&market)?;
+
+        let winning_pool = market.outcome_total(winning_outcome);
+
+        let winning_count = market.outcome_betters(winning_outcome);
+
+        let mut total_actual_payouts = 0i128;
+        let mut actual_winners = 0u32;
+
+        if winning_count > 0 && winning_pool > 0 {
+            let market_users = Self::market_users(&env, market_id);
+
+            // Her kullanıcıyı kontrol et
+            for user in market_users.iter() {
+                let user_bet_key = Self::user_bet_key(market_id, &user, winning_outcome);
+                if let Some(mut user_bet) = env.storage().persistent().get::<(Symbol, u64, Address, u32), UserBet>(&user_bet_key) {
+                    if !user_bet.is_paid_out {
+                        let winnings = payout::winning_payout(&market, &user_bet)?;
+
+                        // Token transferi YOK - sadece test için
+                        Self::mark_paid(&env, &mut user_bet, winnings, false);
+
+                        total_actual_payouts += winnings;
+                        actual_winners += 1;
+                    }
+                }
+            }
+        }
+
+        Self::save_market(&env, &market);
+
+        // DÜZELTİLMİŞ KISIM: Doğru remaining_balance hesaplaması
+        let remaining_balance = prize_pool_after_commission - total_actual_payouts;
+
+        let summary = PayoutSummary {
+            total_winners: actual_winners,
+            total_paid_amount: total_actual_payouts,
+            house_commission,
+            remaining_balance, // Artık doğru hesaplanıyor
+        };
+        events::settled(&env, market_id, &summary);
+        Ok(summary)
+    }
+}
@@ -0,0 +1,92 @@
+//! Reflector fiyat geçmişinden gerçekleşmiş volatilite ve market parametresi önerileri.
+//!
+//! Ardışık kayıtlar arasındaki getiriler (RETURN_SCALE ölçekli) ortalaması sıfır kabul edilerek
+//! karesel ortalamayla varyansa çevrilir; süreye ölçekleme √(süre / kayıt aralığı) ile yapılır.
+//! Fiyat değişimi yaklaşık normal dağılırsa P(|Z| ≤ 0.431) ≈ 1/3 olduğundan, tolerans beklenen
+//! hareketin 0.431 katı seçildiğinde UP, DOWN ve STABLE yaklaşık eşit olasılıkla başlar.
+
+use soroban_sdk::Vec;
+
+use crate::{payout, Error, PriceData};
+
+pub const RETURN_SCALE: i128 = 1_000_000; // 1e6 = %100
+pub const MIN_RECORDS: u32 = 3; // En az iki getiri
+pub const BALANCED_BAND_PERMILLE: i128 = 431; // Üç outcome'u dengeleyen z değeri (x1000)
+pub const MIN_TOLERANCE_BPS: i128 = 25; // Bunun altındaki STABLE bandı oracle gürültüsü kadar
+pub const MAX_SUGGESTED_HOURS: u64 = 168;
+
+/// Kayıt başına getiri varyansı (RETURN_SCALE²) ve ortalama kayıt aralığı (saniye)
+pub fn realized_variance(prices: &Vec<PriceData>) -> Result<(i128, u64), Error> {
+    if prices.len() < MIN_RECORDS {
+        return Err(Error::InsufficientPriceHistory);
+    }
+
+    let mut sum_squares = 0i128;
+    let mut first_timestamp = u64::MAX;
+    let mut last_timestamp = 0u64;
+    for i in 1..prices.len() {
+        let a = prices.get_unchecked(i - 1);
+        let b = prices.get_unchecked(i);
+        // Kayıt sırası oracle'a göre değişebilir - getiri eskiden yeniye
+        let (older, newer) = if a.timestamp <= b.timestamp { (a, b) } else { (b, a) };
+        if older.price <= 0 {
+            return Err(Error::CalculationError);
+        }
+        let change = (newer.price - older.price)
+            .checked_mul(RETURN_SCALE)
+            .ok_or(Error::CalculationError)?
+            / older.price;
+        sum_squares = change
+            .checked_mul(change)
+            .and_then(|square| sum_squares.checked_add(square))
+            .ok_or(Error::CalculationError)?;
+        first_timestamp = first_timestamp.min(older.timestamp);
+        last_timestamp = last_timestamp.max(newer.timestamp);
+    }
+
+    let returns = (prices.len() - 1) as u64;
+    let period_secs = (last_timestamp - first_timestamp) / returns;
+    if period_secs == 0 {
+        return Err(Error::InsufficientPriceHistory);
+    }
+    Ok((sum_squares / returns as i128, period_secs))
+}
+
+/// Varyansa göre `horizon_secs` boyunca beklenen (1σ) hareket, bps
+pub fn expected_move_bps(variance: i128, period_secs: u64, horizon_secs: u64) -> Result<i128, Error> {
+    let horizon_variance = variance
+        .checked_mul(horizon_secs as i128)
+        .map(|v| v / period_secs as i128)
+        .ok_or(Error::CalculationError)?;
+    Ok(isqrt(horizon_variance) * payout::BPS_DENOMINATOR / RETURN_SCALE)
+}
+
+/// Üç outcome'u dengeleyen STABLE toleransı, bps
+pub fn balanced_tolerance(move_bps: i128) -> i128 {
+    move_bps * BALANCED_BAND_PERMILLE / 1000
+}
+
+/// Dengeli toleransın MIN_TOLERANCE_BPS'e ulaştığı en kısa süre (saat, 1..=MAX_SUGGESTED_HOURS)
+pub fn suggested_duration_hours(variance: i128, period_secs: u64) -> Result<u64, Error> {
+    let mut hours = 1u64;
+    while hours < MAX_SUGGESTED_HOURS
+        && balanced_tolerance(expected_move_bps(variance, period_secs, hours * 3600)?) < MIN_TOLERANCE_BPS
+    {
+        hours += 1;
+    }
+    Ok(hours)
+}
+
+/// floor(√x), x ≥ 0 (Newton)
+fn isqrt(x: i128) -> i128 {
+    if x < 2 {
+        return x.max(0);
+    }
+    let mut current = x;
+    let mut next = (x / 2 + 1).min(x);
+    while next < current {
+        current = next;
+        next = (current + x / current) / 2;
+    }
+    current
+}
@@ -0,0 +1,42 @@
+#![cfg(test)]
+
+use bet_prediction::lmsr::{exp_neg, ln, SCALE};
+
+/// Deterministik sözde rastgele sayı üreteci (LCG)
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) % bound
+    }
+}
+
+#[test]
+fn test_lmsr_fixed_point_math() {
+    // Bilinen değerler, 1e-15 hassasiyetle
+    let close = |a: i128, b: i128| (a - b).abs() < 1_000;
+    assert!(close(exp_neg(0).unwrap(), SCALE));
+    assert!(close(exp_neg(-SCALE).unwrap(), 367_879_441_171_442_321));
+    assert!(close(exp_neg(-10 * SCALE).unwrap(), 45_399_929_762_484));
+    assert_eq!(exp_neg(-100 * SCALE).unwrap(), 0);
+    assert!(exp_neg(1).is_err());
+
+    assert_eq!(ln(SCALE).unwrap(), 0);
+    assert!(close(ln(2 * SCALE).unwrap(), 693_147_180_559_945_309));
+    assert!(close(ln(SCALE / 10).unwrap(), -2_302_585_092_994_045_684));
+    assert!(close(ln(8 * SCALE).unwrap(), 2_079_441_541_679_835_928));
+    assert!(ln(0).is_err());
+
+    // ln(e^x) = x
+    let mut rng = Lcg(3);
+    for _ in 0..500 {
+        // e^-20 ≈ 2e-9: bu aralıkta exp'in mutlak hassasiyeti ln için yeterli
+        let x = -(rng.next(20_000_000) as i128) * (SCALE / 1_000_000);
+        let roundtrip = ln(exp_neg(x).unwrap()).unwrap();
+        assert!((roundtrip - x).abs() <= SCALE / 1_000_000_000, "{x}");
+    }
+}
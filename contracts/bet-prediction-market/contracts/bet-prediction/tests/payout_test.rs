@@ -0,0 +1,73 @@
+#![cfg(test)]
+
+use bet_prediction::payout::{house_commission, pro_rata, BPS_DENOMINATOR};
+
+/// Deterministik sözde rastgele sayı üreteci (LCG)
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) % bound
+    }
+}
+
+#[test]
+fn prop_total_payouts_plus_commission_within_volume() {
+    let mut rng = Lcg(42);
+
+    for _ in 0..2_000 {
+        let house_edge = rng.next(BPS_DENOMINATOR as u64 + 1) as i128;
+        let winner_count = rng.next(20) as usize + 1;
+
+        let mut stakes = std::vec::Vec::new();
+        for _ in 0..winner_count {
+            stakes.push(rng.next(1_000_000_000_000) as i128 + 1);
+        }
+        let winning_pool: i128 = stakes.iter().sum();
+        let losing_pool = rng.next(1_000_000_000_000_000) as i128;
+        let volume = winning_pool + losing_pool;
+
+        let commission = house_commission(volume, house_edge).unwrap();
+        let prize_pool = volume - commission;
+
+        let mut paid = 0i128;
+        for stake in stakes.iter() {
+            paid += pro_rata(*stake, winning_pool, prize_pool).unwrap();
+        }
+
+        assert!(paid + commission <= volume);
+        // Yuvarlama artığı kazanan sayısından az
+        assert!(prize_pool - paid < winner_count as i128);
+    }
+}
+
+#[test]
+fn prop_pro_rata_is_monotonic_and_exact() {
+    let mut rng = Lcg(7);
+
+    for _ in 0..2_000 {
+        let total = rng.next(1_000_000_000_000_000) as i128 + 1;
+        let pool = rng.next(1_000_000_000_000_000) as i128;
+        let a = rng.next(total as u64 + 1) as i128;
+        let b = rng.next(total as u64 + 1) as i128;
+        let (small, large) = if a <= b { (a, b) } else { (b, a) };
+
+        assert!(pro_rata(small, total, pool).unwrap() <= pro_rata(large, total, pool).unwrap());
+        assert_eq!(pro_rata(total, total, pool).unwrap(), pool);
+        assert_eq!(pro_rata(0, total, pool).unwrap(), 0);
+    }
+}
+
+#[test]
+fn test_invalid_inputs_rejected() {
+    assert!(house_commission(1_000, BPS_DENOMINATOR + 1).is_err());
+    assert!(house_commission(1_000, -1).is_err());
+    assert!(pro_rata(11, 10, 100).is_err());
+    assert!(pro_rata(-1, 10, 100).is_err());
+    assert!(pro_rata(i128::MAX, i128::MAX, 2).is_err());
+    assert_eq!(pro_rata(0, 0, 100).unwrap(), 0);
+}
@@ -0,0 +1,2289 @@
+#![cfg(test)]
+
+use bet_prediction::{
+    events::{
+        AdminTransferredEvent, BetPlacedEvent, ConfigChange, ListingCancelledEvent, MarketClosedEvent,
+        MarketResolvedEvent, MarketVoidedEvent, PositionTransferredEvent, SeriesStoppedEvent,
+        SharesTradedEvent, EVENT_VERSION,
+    },
+    BetLimits, DisputeConfig,
+    Error, FixedOddsConfig, MarketConfig, MarketFilter, MarketSchedule, MarketStatus,
+    MarketSuggestion, OutcomeRule, PredictionMarket, PredictionMarketClient, PriceFeed, PricingMode,
+    ReflectorAsset, Role, SeriesTemplate, SettlementMode, UserStats, VoidReason,
+};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, vec, Address, Env, IntoVal, String, Symbol, Vec,
+};
+
+const ORACLE_ADDRESS_STR: &str = "CAVLP5DH2GJPZMVO7IJY4CVOD5MWEFTJFVPD2YY2FQXOQHRGHK4D6HLP";
+
+// Reflector ile aynı şekle sahip tipler
+#[contracttype]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+#[contracttype]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Test için sahte Reflector oracle
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_spot(env: Env, price: i128) {
+        env.storage().instance().set(&symbol_short!("SPOT"), &price);
+    }
+
+    pub fn set_twap(env: Env, price: i128) {
+        env.storage().instance().set(&symbol_short!("TWAP"), &price);
+    }
+
+    pub fn set_history(env: Env, prices: Vec<i128>) {
+        env.storage().instance().set(&symbol_short!("HIST"), &prices);
+    }
+
+    pub fn set_price_at(env: Env, timestamp: u64, price: i128) {
+        env.storage().instance().set(&(symbol_short!("AT"), timestamp), &price);
+    }
+
+    pub fn price(env: Env, _asset: Asset, timestamp: u64) -> Option<PriceData> {
+        let price: Option<i128> = env.storage().instance().get(&(symbol_short!("AT"), timestamp));
+        price.map(|price| PriceData { price, timestamp })
+    }
+
+    pub fn lastprice(env: Env, _asset: Asset) -> Option<PriceData> {
+        let price: Option<i128> = env.storage().instance().get(&symbol_short!("SPOT"));
+        price.map(|price| PriceData {
+            price,
+            timestamp: env.ledger().timestamp(),
+        })
+    }
+
+    pub fn twap(env: Env, _asset: Asset, _records: u32) -> Option<i128> {
+        env.storage().instance().get(&symbol_short!("TWAP"))
+    }
+
+    pub fn set_cross(env: Env, price: i128) {
+        env.storage().instance().set(&symbol_short!("CROSS"), &price);
+    }
+
+    pub fn set_cross_at(env: Env, timestamp: u64, price: i128) {
+        env.storage().instance().set(&(symbol_short!("XAT"), timestamp), &price);
+    }
+
+    pub fn x_last_price(env: Env, _base_asset: Asset, _quote_asset: Asset) -> Option<PriceData> {
+        let price: Option<i128> = env.storage().instance().get(&symbol_short!("CROSS"));
+        price.map(|price| PriceData {
+            price,
+            timestamp: env.ledger().timestamp(),
+        })
+    }
+
+    pub fn x_price(
+        env: Env,
+        _base_asset: Asset,
+        _quote_asset: Asset,
+        timestamp: u64,
+    ) -> Option<PriceData> {
+        let price: Option<i128> = env
+            .storage()
+            .instance()
+            .get(&(symbol_short!("XAT"), timestamp));
+        price.map(|price| PriceData { price, timestamp })
+    }
+
+    pub fn prices(env: Env, _asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        let history: Vec<i128> = env.storage().instance().get(&symbol_short!("HIST"))?;
+        let mut result = Vec::new(&env);
+        // En yeni kayıt başta, kayıtlar 5 dakika arayla
+        for (i, price) in history.iter().take(records as usize).enumerate() {
+            result.push_back(PriceData {
+                price,
+                timestamp: env.ledger().timestamp().saturating_sub(300 * i as u64),
+            });
+        }
+        Some(result)
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    client: PredictionMarketClient<'a>,
+    oracle: MockOracleClient<'a>,
+    admin: Address,
+    token: Address,
+    betting_token: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let oracle_address = Address::from_string(&String::from_str(&env, ORACLE_ADDRESS_STR));
+    env.register_at(&oracle_address, MockOracle, ());
+    let oracle = MockOracleClient::new(&env, &oracle_address);
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(PredictionMarket, (&admin,));
+    let client = PredictionMarketClient::new(&env, &contract_id);
+
+    let token = Address::generate(&env);
+    let betting_token = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+
+    Setup {
+        env,
+        client,
+        oracle,
+        admin,
+        token,
+        betting_token,
+    }
+}
+
+fn xlm_feed(s: &Setup) -> PriceFeed {
+    PriceFeed::Single(ReflectorAsset::Stellar(s.token.clone()))
+}
+
+fn hours(duration_hours: u64) -> MarketSchedule {
+    MarketSchedule {
+        start_time: 0,
+        duration_hours,
+        betting_cutoff_secs: 0,
+        auto_restart: false,
+        series_id: None,
+    }
+}
+
+fn no_limits() -> BetLimits {
+    BetLimits {
+        min_bet: 0,
+        max_bet: 0,
+        max_user_total: 0,
+        max_side_share_bps: 0,
+        imbalance_limit_bps: 0,
+        share_checks_from: 0,
+    }
+}
+
+fn create_market(s: &Setup, settlement_mode: &SettlementMode) -> u64 {
+    create_market_with_rule(s, settlement_mode, &OutcomeRule::RelativeBand)
+}
+
+fn create_market_with_rule(
+    s: &Setup,
+    settlement_mode: &SettlementMode,
+    outcome_rule: &OutcomeRule,
+) -> u64 {
+    create_market_with_pricing(s, settlement_mode, outcome_rule, &PricingMode::PariMutuel)
+}
+
+/// Varsayılan market kuralları - testler yalnızca farklı alanları değiştirir
+fn base_config(s: &Setup) -> MarketConfig {
+    MarketConfig {
+        target_price: 1_000_000,
+        stable_tolerance: 100, // %1
+        house_edge: 0,
+        settlement_mode: SettlementMode::Spot,
+        outcome_rule: OutcomeRule::RelativeBand,
+        pricing: PricingMode::PariMutuel,
+        limits: no_limits(),
+        liquidity: Vec::new(&s.env),
+    }
+}
+
+fn try_create_market_with_config(s: &Setup, feed: &PriceFeed, config: &MarketConfig) -> Result<u64, Error> {
+    s.client
+        .try_create_market(
+            &s.admin,
+            &String::from_str(&s.env, "XLM 24h"),
+            feed,
+            &s.betting_token,
+            &hours(24),
+            config,
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create_market_with_pricing(
+    s: &Setup,
+    settlement_mode: &SettlementMode,
+    outcome_rule: &OutcomeRule,
+    pricing: &PricingMode,
+) -> u64 {
+    let config = MarketConfig {
+        settlement_mode: settlement_mode.clone(),
+        outcome_rule: outcome_rule.clone(),
+        pricing: pricing.clone(),
+        ..base_config(s)
+    };
+    try_create_market_with_config(s, &xlm_feed(s), &config).unwrap()
+}
+
+fn bet(s: &Setup, market_id: u64, amount: i128, prediction: u32) -> Address {
+    let user = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&user, &amount);
+    s.client.place_bet(&user, &market_id, &amount, &prediction);
+    user
+}
+
+#[test]
+fn test_hello() {
+    let s = setup();
+    assert_eq!(
+        s.client.hello(),
+        String::from_str(&s.env, "Enhanced Prediction Market v3.0 with Auto Payouts!")
+    );
+}
+
+#[test]
+fn test_spot_settlement() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+
+    let up_user = bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 1_000, 1);
+
+    // Spot +%5, TWAP düz: spot marketi Up çözülmeli
+    s.oracle.set_spot(&1_050_000);
+    s.oracle.set_twap(&1_000_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.winning_outcome, Some(0));
+    assert_eq!(market.final_price, Some(1_050_000));
+    assert!(s.client.get_user_bet(&market_id, &up_user, &0).unwrap().is_paid_out);
+}
+
+#[test]
+fn test_twap_settlement_ignores_last_minute_spike() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    s.client.set_dispute_config(&s.admin, &86_400, &0);
+    let market_id = create_market(&s, &SettlementMode::Twap(10));
+
+    bet(&s, market_id, 1_000, 0);
+    let stable_user = bet(&s, market_id, 1_000, 2);
+
+    // Son dakika spike'ı TWAP'ı etkilemez
+    let end_time = s.client.get_market(&market_id).unwrap().end_time;
+    s.env.ledger().with_mut(|l| l.timestamp = end_time);
+    s.oracle.set_spot(&1_200_000);
+    s.oracle.set_price_at(&end_time, &1_200_000);
+    s.oracle.set_twap(&1_005_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.winning_outcome, Some(2));
+    assert_eq!(market.final_price, Some(1_005_000));
+
+    // Aynı spike itiraz kanıtı olarak da kullanılamaz
+    let challenger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_dispute_resolution(&challenger, &market_id),
+        Err(Ok(Error::InvalidDispute))
+    );
+
+    s.env.ledger().with_mut(|l| l.timestamp += 86_400);
+    s.client.process_payouts_improved(&s.admin, &market_id);
+    assert!(s.client.get_user_bet(&market_id, &stable_user, &2).unwrap().is_paid_out);
+}
+
+#[test]
+fn test_average_settlement() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Average(3));
+
+    bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 1_000, 1);
+
+    s.oracle.set_spot(&1_100_000);
+    let mut history = Vec::new(&s.env);
+    history.push_back(900_000i128);
+    history.push_back(950_000i128);
+    history.push_back(970_000i128);
+    history.push_back(1_100_000i128); // records=3 dışında kalır
+    s.oracle.set_history(&history);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.final_price, Some(940_000));
+    assert_eq!(market.winning_outcome, Some(1));
+}
+
+#[test]
+fn test_manual_resolution_honors_twap_mode() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Twap(10));
+
+    bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 1_000, 1);
+
+    // Admin'in verdiği spot fiyat yerine TWAP kullanılır
+    s.oracle.set_twap(&900_000);
+    let winning_side = s.client.resolve_market_manual_only(&s.admin, &market_id, &1_500_000);
+
+    assert_eq!(winning_side, 1);
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.final_price, Some(900_000));
+}
+
+#[test]
+fn test_settlement_fails_without_oracle_data() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Twap(10));
+
+    let result = s.client.try_resolve_and_payout_market(&s.admin, &market_id);
+    assert!(result.is_err());
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Open);
+}
+
+#[test]
+fn test_invalid_settlement_mode_rejected() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+
+    let result = s.client.try_create_market(
+        &s.admin,
+        &String::from_str(&s.env, "XLM 24h"),
+        &xlm_feed(&s),
+        &s.betting_token,
+        &hours(24),
+        &MarketConfig {
+            target_price: 1_000_000,
+            stable_tolerance: 100,
+            house_edge: 0,
+            settlement_mode: SettlementMode::Twap(0),
+            outcome_rule: OutcomeRule::RelativeBand,
+            pricing: PricingMode::PariMutuel,
+            limits: no_limits(),
+            liquidity: Vec::new(&s.env),
+        },
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_all_resolution_paths_agree() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let auto_market = create_market(&s, &SettlementMode::Spot);
+    let manual_market = create_market(&s, &SettlementMode::Spot);
+    let manual_only_market = create_market(&s, &SettlementMode::Spot);
+
+    for market_id in [auto_market, manual_market, manual_only_market] {
+        bet(&s, market_id, 1_000, 0);
+        bet(&s, market_id, 1_000, 2);
+    }
+
+    // +%0.5 değişim: 100 bps tolerans içinde -> Stable
+    s.oracle.set_spot(&1_005_000);
+    s.client.resolve_and_payout_market(&s.admin, &auto_market);
+    s.client.resolve_market_manual(&s.admin, &manual_market, &1_005_000);
+    s.client.resolve_market_manual_only(&s.admin, &manual_only_market, &1_005_000);
+
+    for market_id in [auto_market, manual_market, manual_only_market] {
+        assert_eq!(s.client.get_market(&market_id).unwrap().winning_outcome, Some(2));
+    }
+}
+
+#[test]
+fn test_absolute_band_and_strike_rules() {
+    let s = setup();
+    s.oracle.set_spot(&900_000);
+    let band_market = create_market_with_rule(&s, &SettlementMode::Spot, &OutcomeRule::AbsoluteBand);
+    let strike_market = create_market_with_rule(&s, &SettlementMode::Spot, &OutcomeRule::Strike);
+
+    // target_price 1_000_000, tolerans 100
+    assert_eq!(s.client.resolve_market_manual_only(&s.admin, &band_market, &1_000_050), 2);
+    assert_eq!(s.client.resolve_market_manual_only(&s.admin, &strike_market, &1_000_050), 0);
+}
+
+#[test]
+fn test_bucket_rule() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+
+    let mut boundaries = Vec::new(&s.env);
+    boundaries.push_back(950_000i128);
+    boundaries.push_back(1_050_000i128);
+    let rule = OutcomeRule::Buckets(boundaries);
+
+    let low = create_market_with_rule(&s, &SettlementMode::Spot, &rule);
+    let mid = create_market_with_rule(&s, &SettlementMode::Spot, &rule);
+    let high = create_market_with_rule(&s, &SettlementMode::Spot, &rule);
+
+    assert_eq!(s.client.resolve_market_manual_only(&s.admin, &low, &900_000), 0);
+    assert_eq!(s.client.resolve_market_manual_only(&s.admin, &mid, &950_000), 1);
+    assert_eq!(s.client.resolve_market_manual_only(&s.admin, &high, &1_100_000), 2);
+}
+
+#[test]
+fn test_invalid_bucket_rule_rejected() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+
+    let mut boundaries = Vec::new(&s.env);
+    boundaries.push_back(1_050_000i128);
+    boundaries.push_back(950_000i128);
+
+    let result = s.client.try_create_market(
+        &s.admin,
+        &String::from_str(&s.env, "XLM 24h"),
+        &xlm_feed(&s),
+        &s.betting_token,
+        &hours(24),
+        &MarketConfig {
+            target_price: 1_000_000,
+            stable_tolerance: 100,
+            house_edge: 0,
+            settlement_mode: SettlementMode::Spot,
+            outcome_rule: OutcomeRule::Buckets(boundaries),
+            pricing: PricingMode::PariMutuel,
+            limits: no_limits(),
+            liquidity: Vec::new(&s.env),
+        },
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_multi_outcome_bucket_market() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+
+    let mut boundaries = Vec::new(&s.env);
+    boundaries.push_back(900_000i128);
+    boundaries.push_back(1_000_000i128);
+    boundaries.push_back(1_100_000i128);
+    let market_id = create_market_with_rule(&s, &SettlementMode::Spot, &OutcomeRule::Buckets(boundaries));
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.outcomes.len(), 4);
+    assert_eq!(market.outcomes.get(3).unwrap().label, Symbol::new(&s.env, "B3"));
+
+    bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 3_000, 3);
+    let winner = bet(&s, market_id, 1_000, 3);
+
+    let stats = s.client.get_market_stats(&market_id);
+    assert_eq!(stats.total_volume, 5_000);
+    assert_eq!(stats.total_betters, 3);
+    assert_eq!(stats.outcome_percentages.len(), 4);
+    assert_eq!(stats.outcome_percentages.get(3).unwrap(), 8_000);
+    assert_eq!(s.client.calculate_improved_odds(&market_id).len(), 4);
+
+    s.oracle.set_spot(&1_150_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.winning_outcome, Some(3));
+    // 5_000 / 4_000 * 1_000
+    assert_eq!(s.client.get_user_bet(&market_id, &winner, &3).unwrap().winnings, 1_250);
+}
+
+#[test]
+fn test_bet_on_unknown_outcome_rejected() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market_with_rule(&s, &SettlementMode::Spot, &OutcomeRule::Strike);
+
+    let user = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&user, &1_000);
+    assert!(s.client.try_place_bet(&user, &market_id, &1_000, &2).is_err());
+}
+
+#[test]
+fn test_multiple_bets_and_hedging() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+
+    let user = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&user, &6_000);
+
+    // Aynı tarafa iki kez ve karşı tarafa hedge
+    s.client.place_bet(&user, &market_id, &1_000, &0);
+    s.client.place_bet(&user, &market_id, &2_000, &0);
+    s.client.place_bet(&user, &market_id, &1_000, &1);
+    bet(&s, market_id, 2_000, 1);
+
+    let up_position = s.client.get_user_bet(&market_id, &user, &0).unwrap();
+    assert_eq!(up_position.amount, 3_000);
+    assert_eq!(up_position.entries.len(), 2);
+    assert_eq!(up_position.entries.get(1).unwrap().amount, 2_000);
+    assert_eq!(s.client.get_user_positions(&market_id, &user).len(), 2);
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.outcomes.get(0).unwrap().total_bets, 3_000);
+    assert_eq!(market.outcomes.get(0).unwrap().betters_count, 1);
+    assert_eq!(market.outcomes.get(1).unwrap().betters_count, 2);
+
+    // Up kazanır: toplam pozisyon üzerinden 6_000 havuzun tamamı
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+
+    let up_position = s.client.get_user_bet(&market_id, &user, &0).unwrap();
+    assert!(up_position.is_paid_out);
+    assert_eq!(up_position.winnings, 6_000);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+    assert_eq!(token_client.balance(&user), 8_000);
+}
+
+#[test]
+fn test_cash_out_before_close() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let down_user = bet(&s, market_id, 1_000, 1);
+    let up_user = bet(&s, market_id, 1_000, 0); // odds 2.0x
+    let late_down_user = bet(&s, market_id, 2_000, 1); // Up odds 4.0x'e uzar
+
+    // 1_000 * 2000 / 4000 = 500, %2 fee = 10
+    assert_eq!(s.client.get_cash_out_value(&up_user, &market_id, &0), 490);
+    assert_eq!(s.client.cash_out(&up_user, &market_id, &0), 490);
+    assert_eq!(token_client.balance(&up_user), 490);
+    assert_eq!(s.client.get_user_bet(&market_id, &up_user, &0), None);
+    // Çıkan kullanıcı ödeme turlarında yer almaz - sonucu çıkışta işlenir
+    assert_eq!(s.client.get_user_stats(&up_user, &s.betting_token), stats(1, 0, 1_000, -510, 10));
+    let board = s.client.get_leaderboard(&1, &s.betting_token, &10);
+    assert_eq!(board.get(0).unwrap().user, up_user);
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.outcomes.get(0).unwrap().total_bets, 0);
+    assert_eq!(market.outcomes.get(0).unwrap().betters_count, 0);
+    assert_eq!(market.cash_out_surplus, 510);
+
+    // Down kazanır: kalan 510 kazananlara dağıtılır (3_510 havuz)
+    s.oracle.set_spot(&900_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(token_client.balance(&down_user), 1_170);
+    assert_eq!(token_client.balance(&late_down_user), 2_340);
+    assert_eq!(s.client.get_user_stats(&up_user, &s.betting_token), stats(1, 0, 1_000, -510, 10));
+}
+
+#[test]
+fn test_cash_out_cutoff_window() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let user = bet(&s, market_id, 1_000, 0);
+
+    let end_time = s.client.get_market(&market_id).unwrap().end_time;
+    s.env.ledger().with_mut(|l| l.timestamp = end_time - 1_800);
+
+    assert!(s.client.try_cash_out(&user, &market_id, &0).is_err());
+    assert!(s.client.try_cash_out(&user, &market_id, &1).is_err());
+}
+
+#[test]
+fn test_transfer_position_pays_current_holder() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let original = bet(&s, market_id, 1_000, 0);
+    let holder = bet(&s, market_id, 500, 0);
+    bet(&s, market_id, 1_500, 1);
+
+    // Birleşen pozisyon: holder'ın Up pozisyonu 1_500 olur
+    s.client.transfer_position(&original, &holder, &market_id, &0);
+    assert_eq!(s.client.get_user_bet(&market_id, &original, &0), None);
+    let position = s.client.get_user_bet(&market_id, &holder, &0).unwrap();
+    assert_eq!(position.amount, 1_500);
+    assert_eq!(position.entries.len(), 2);
+    assert_eq!(s.client.get_market(&market_id).unwrap().outcomes.get(0).unwrap().betters_count, 1);
+
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(token_client.balance(&original), 0);
+    assert_eq!(token_client.balance(&holder), 3_000);
+}
+
+#[test]
+fn test_list_and_buy_position() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let seller = bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 1_000, 1);
+
+    s.client.list_position(&seller, &market_id, &0, &1_200);
+    assert_eq!(s.client.get_listings(&market_id).len(), 1);
+
+    let buyer = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&buyer, &1_200);
+    assert_eq!(s.client.buy_position(&buyer, &seller, &market_id, &0), 1_200);
+
+    assert_eq!(token_client.balance(&seller), 1_200);
+    assert_eq!(token_client.balance(&buyer), 0);
+    assert_eq!(s.client.get_listings(&market_id).len(), 0);
+    assert_eq!(s.client.get_user_bet(&market_id, &buyer, &0).unwrap().user, buyer);
+
+    // İlan kalmadı: ikinci alım başarısız
+    assert!(s.client.try_buy_position(&buyer, &seller, &market_id, &0).is_err());
+
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(token_client.balance(&buyer), 2_000);
+}
+
+#[test]
+fn test_payouts_never_exceed_pool() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    // Sözde rastgele bahisler: ödemeler + komisyon hacmi asla geçmemeli
+    let mut seed = 0x2545_f491u64;
+    for round in 0..5u64 {
+        let market_id = s.client.create_market(
+            &s.admin,
+            &String::from_str(&s.env, "XLM 24h"),
+            &xlm_feed(&s),
+            &s.betting_token,
+            &hours(24),
+            &MarketConfig {
+                target_price: 1_000_000,
+                stable_tolerance: 100,
+                house_edge: round as i128 * 137,
+                settlement_mode: SettlementMode::Spot,
+                outcome_rule: OutcomeRule::RelativeBand,
+                pricing: PricingMode::PariMutuel,
+                limits: no_limits(),
+                liquidity: Vec::new(&s.env),
+            },
+        );
+
+        let mut winners = std::vec::Vec::new();
+        let mut volume = 0i128;
+        for _ in 0..7 {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            let amount = (seed >> 33) as i128 % 9_973 + 1;
+            let outcome = ((seed >> 20) % 3) as u32;
+            let user = bet(&s, market_id, amount, outcome);
+            volume += amount;
+            if outcome == 0 {
+                winners.push(user);
+            }
+        }
+
+        let contract_before = token_client.balance(&s.client.address);
+        s.oracle.set_spot(&1_100_000);
+        let summary = s.client.resolve_and_payout_market(&s.admin, &market_id);
+        s.oracle.set_spot(&1_000_000);
+
+        let paid: i128 = winners.iter().map(|w| token_client.balance(w)).sum();
+        assert_eq!(paid, summary.total_paid_amount);
+        assert!(summary.total_paid_amount + summary.house_commission <= volume);
+        // Dust en fazla kazanan sayısı kadar
+        if !winners.is_empty() {
+            assert!(summary.remaining_balance >= 0);
+            assert!(summary.remaining_balance < winners.len() as i128);
+        }
+        assert_eq!(token_client.balance(&s.client.address), contract_before - paid);
+    }
+}
+
+fn fixed_odds(s: &Setup, opening_odds: &[i128], max_exposure: i128) -> PricingMode {
+    PricingMode::FixedOdds(FixedOddsConfig {
+        opening_odds: Vec::from_slice(&s.env, opening_odds),
+        max_exposure,
+    })
+}
+
+fn fund(s: &Setup, market_id: u64, amount: i128) {
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&s.admin, &amount);
+    s.client.fund_bankroll(&s.admin, &market_id, &amount);
+}
+
+#[test]
+fn test_fixed_odds_locks_odds_and_pays_from_bankroll() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let pricing = fixed_odds(&s, &[1_900, 1_900], 10_000);
+    let market_id =
+        create_market_with_pricing(&s, &SettlementMode::Spot, &OutcomeRule::Strike, &pricing);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+    fund(&s, market_id, 5_000);
+
+    let up_user = bet(&s, market_id, 1_000, 0);
+    // House Up'ta 900 net risk taşıyor: Up oranı düşer, Down aynı kalır
+    let odds = s.client.calculate_improved_odds(&market_id);
+    assert_eq!(odds.get(0).unwrap(), 1_819);
+    assert_eq!(odds.get(1).unwrap(), 1_900);
+
+    let down_user = bet(&s, market_id, 1_000, 1);
+    let up_position = s.client.get_user_bet(&market_id, &up_user, &0).unwrap();
+    assert_eq!(up_position.entries.get(0).unwrap().odds_when_placed, 1_900);
+
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.outcomes.get(0).unwrap().liability, 1_900);
+    assert_eq!(market.outcomes.get(1).unwrap().liability, 1_900);
+
+    // Up kazanır: kilitli 1.9x oran, havuz payı değil
+    s.oracle.set_spot(&1_100_000);
+    let summary = s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(summary.total_paid_amount, 1_900);
+    assert_eq!(token_client.balance(&up_user), 1_900);
+    assert_eq!(token_client.balance(&down_user), 0);
+
+    // Bankroll + stake'ler - ödeme creator'a döner
+    assert_eq!(s.client.withdraw_bankroll(&s.admin, &market_id), 5_100);
+    assert_eq!(token_client.balance(&s.client.address), 0);
+    assert_eq!(
+        s.client.try_withdraw_bankroll(&s.admin, &market_id),
+        Err(Ok(Error::InsufficientBankroll))
+    );
+}
+
+#[test]
+fn test_fixed_odds_refuses_uncovered_bets() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let pricing = fixed_odds(&s, &[1_900, 1_900], 800);
+    let market_id =
+        create_market_with_pricing(&s, &SettlementMode::Spot, &OutcomeRule::Strike, &pricing);
+
+    let user = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&user, &2_000);
+
+    // Bankroll yok: 720 net risk karşılanamaz
+    assert_eq!(
+        s.client.try_place_bet(&user, &market_id, &800, &0),
+        Err(Ok(Error::InsufficientBankroll))
+    );
+
+    // Bankroll yeterli ama outcome limiti 800
+    fund(&s, market_id, 5_000);
+    assert_eq!(
+        s.client.try_place_bet(&user, &market_id, &1_000, &0),
+        Err(Ok(Error::ExposureLimitExceeded))
+    );
+    s.client.place_bet(&user, &market_id, &800, &0);
+    assert_eq!(
+        s.client.try_cash_out(&user, &market_id, &0),
+        Err(Ok(Error::UnsupportedPricingMode))
+    );
+}
+
+#[test]
+fn test_fixed_odds_config_validated() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+
+    let create = |pricing: &PricingMode, house_edge: i128| {
+        s.client.try_create_market(
+            &s.admin,
+            &String::from_str(&s.env, "XLM 24h"),
+            &xlm_feed(&s),
+            &s.betting_token,
+            &hours(24),
+            &MarketConfig {
+                target_price: 1_000_000,
+                stable_tolerance: 100,
+                house_edge,
+                settlement_mode: SettlementMode::Spot,
+                outcome_rule: OutcomeRule::Strike,
+                pricing: pricing.clone(),
+                limits: no_limits(),
+                liquidity: Vec::new(&s.env),
+            },
+        )
+    };
+
+    assert_eq!(
+        create(&fixed_odds(&s, &[1_900, 1_900, 1_900], 1_000), 0),
+        Err(Ok(Error::InvalidPricingMode))
+    );
+    assert_eq!(
+        create(&fixed_odds(&s, &[1_900, 1_000], 1_000), 0),
+        Err(Ok(Error::InvalidPricingMode))
+    );
+    assert_eq!(
+        create(&fixed_odds(&s, &[1_900, 1_900], 1_000), 50),
+        Err(Ok(Error::InvalidHouseEdge))
+    );
+    assert!(create(&fixed_odds(&s, &[1_900, 1_900], 1_000), 0).is_ok());
+}
+
+fn lmsr_market(s: &Setup, liquidity: i128) -> u64 {
+    create_market_with_pricing(
+        s,
+        &SettlementMode::Spot,
+        &OutcomeRule::Strike,
+        &PricingMode::Lmsr(liquidity),
+    )
+}
+
+fn buy(s: &Setup, market_id: u64, outcome: u32, shares: i128) -> (Address, i128) {
+    let user = Address::generate(&s.env);
+    let cost = s.client.quote_buy_shares(&market_id, &outcome, &shares);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&user, &cost);
+    assert_eq!(s.client.buy_shares(&user, &market_id, &outcome, &shares, &cost), cost);
+    (user, cost)
+}
+
+#[test]
+fn test_lmsr_prices_move_with_trades() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = lmsr_market(&s, 100_000);
+
+    // Sübvansiyon yatırılmadan işlem yok: b * ln(2) = 69_315
+    let user = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_buy_shares(&user, &market_id, &0, &1_000, &i128::MAX),
+        Err(Ok(Error::InsufficientBankroll))
+    );
+    fund(&s, market_id, 69_314);
+    assert_eq!(
+        s.client.try_buy_shares(&user, &market_id, &0, &1_000, &i128::MAX),
+        Err(Ok(Error::InsufficientBankroll))
+    );
+    fund(&s, market_id, 1);
+
+    // Başlangıçta fiyatlar eşit
+    let stats = s.client.get_market_stats(&market_id);
+    assert_eq!(stats.outcome_percentages.get(0).unwrap(), 5_000);
+
+    // 100_000 pay: b * ln((e + 1) / 2) = 62_011.4, yuvarlama market maker lehine
+    let (_, cost) = buy(&s, market_id, 0, 100_000);
+    assert_eq!(cost, 62_013);
+    let stats = s.client.get_market_stats(&market_id);
+    assert_eq!(stats.outcome_percentages.get(0).unwrap(), 7_310);
+    assert_eq!(stats.outcome_percentages.get(1).unwrap(), 2_689);
+
+    // Fiyat yükseldi: aynı miktar pay daha pahalı, slipaj limiti korur
+    let next_cost = s.client.quote_buy_shares(&market_id, &0, &100_000);
+    assert!(next_cost > cost);
+    assert_eq!(
+        s.client.try_buy_shares(&user, &market_id, &0, &100_000, &cost),
+        Err(Ok(Error::SlippageExceeded))
+    );
+    assert_eq!(
+        s.client.try_place_bet(&user, &market_id, &1_000, &0),
+        Err(Ok(Error::UnsupportedPricingMode))
+    );
+}
+
+#[test]
+fn test_lmsr_round_trip_and_resolution() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = lmsr_market(&s, 100_000);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+    fund(&s, market_id, 70_000);
+
+    let (up_user, up_cost) = buy(&s, market_id, 0, 150_000);
+    let (down_user, _) = buy(&s, market_id, 1, 50_000);
+
+    // Kısmi satış: maliyet tabanı orantılı azalır, yuvarlama market maker lehine
+    s.client.list_position(&up_user, &market_id, &0, &100_000);
+    let proceeds = s.client.sell_shares(&up_user, &market_id, &0, &50_000, &0);
+    assert!(proceeds > 0 && proceeds < up_cost);
+    let position = s.client.get_user_bet(&market_id, &up_user, &0).unwrap();
+    assert_eq!(position.shares, 100_000);
+    assert_eq!(position.amount, up_cost - up_cost / 3);
+    // İlan 150_000 pay içindi - kalan 100_000 pay eski fiyattan satılamaz
+    assert!(s.client.get_listings(&market_id).is_empty());
+    assert_eq!(
+        s.client.try_sell_shares(&down_user, &market_id, &1, &50_001, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    // Up kazanır: pay başına 1 birim
+    s.oracle.set_spot(&1_100_000);
+    let summary = s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(summary.total_paid_amount, 100_000);
+    assert_eq!(token_client.balance(&up_user), proceeds + 100_000);
+    assert_eq!(token_client.balance(&down_user), 0);
+
+    // Creator kalan her şeyi alır, kontrat boşalır
+    let contract_balance = token_client.balance(&s.client.address);
+    assert_eq!(s.client.withdraw_bankroll(&s.admin, &market_id), contract_balance);
+    assert_eq!(token_client.balance(&s.client.address), 0);
+}
+
+#[test]
+fn test_commission_accrues_to_treasury() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    s.client.set_creator_fee_share(&s.admin, &2_000); // Komisyonun %20'si creator'a
+
+    let market_id = s.client.create_market(
+        &s.admin,
+        &String::from_str(&s.env, "XLM 24h"),
+        &xlm_feed(&s),
+        &s.betting_token,
+        &hours(24),
+        &MarketConfig {
+            target_price: 1_000_000,
+            stable_tolerance: 100,
+            house_edge: 500, // %5
+            settlement_mode: SettlementMode::Spot,
+            outcome_rule: OutcomeRule::RelativeBand,
+            pricing: PricingMode::PariMutuel,
+            limits: no_limits(),
+            liquidity: Vec::new(&s.env),
+        },
+    );
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 2_001, 0);
+    bet(&s, market_id, 1_000, 1);
+
+    let report = s.client.get_treasury_report(&s.betting_token);
+    assert_eq!(report.locked_in_markets, 4_001);
+    assert_eq!(report.accrued_commission, 0);
+    assert_eq!(report.unallocated, 0);
+
+    // Komisyon 200: 160 hazineye, 40 creator'a; 3_801 havuzdan 1 birim dust kalır
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+
+    let report = s.client.get_treasury_report(&s.betting_token);
+    assert_eq!(report.accrued_commission, 160);
+    assert_eq!(report.creator_fees, 40);
+    assert_eq!(report.locked_in_markets, 0);
+    assert_eq!(report.contract_balance, 201);
+    assert_eq!(report.unallocated, 1);
+
+    // Sadece birikmiş komisyon çekilebilir
+    let treasurer = Address::generate(&s.env);
+    let outsider = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_withdraw_commission(&outsider, &s.betting_token, &outsider, &160),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        s.client.try_withdraw_commission(&s.admin, &s.betting_token, &treasurer, &161),
+        Err(Ok(Error::InsufficientContractBalance))
+    );
+    assert_eq!(s.client.withdraw_commission(&s.admin, &s.betting_token, &treasurer, &160), 0);
+    assert_eq!(token_client.balance(&treasurer), 160);
+
+    assert_eq!(s.client.claim_creator_fees(&s.admin, &s.betting_token), 40);
+    assert_eq!(s.client.get_creator_fees(&s.admin, &s.betting_token), 0);
+    assert_eq!(token_client.balance(&s.client.address), 1);
+}
+
+#[test]
+fn test_privileged_functions_require_roles() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+
+    let creator = Address::generate(&s.env);
+    let resolver = Address::generate(&s.env);
+    let create = |caller: &Address| {
+        s.client.try_create_market(
+            caller,
+            &String::from_str(&s.env, "XLM 24h"),
+            &xlm_feed(&s),
+            &s.betting_token,
+            &MarketSchedule {
+                auto_restart: true,
+                ..hours(24)
+            },
+            &MarketConfig {
+                target_price: 1_000_000,
+                stable_tolerance: 100,
+                house_edge: 0,
+                settlement_mode: SettlementMode::Spot,
+                outcome_rule: OutcomeRule::RelativeBand,
+                pricing: PricingMode::PariMutuel,
+                limits: no_limits(),
+                liquidity: Vec::new(&s.env),
+            },
+        )
+    };
+
+    // Kendini admin diye geçirmek yetmez
+    assert_eq!(create(&creator), Err(Ok(Error::NotAuthorized)));
+    assert_eq!(
+        s.client.try_grant_role(&creator, &Role::MarketCreator, &creator),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    s.client.grant_role(&s.admin, &Role::MarketCreator, &creator);
+    s.client.grant_role(&s.admin, &Role::Resolver, &resolver);
+    assert!(s.client.has_role(&Role::MarketCreator, &creator));
+    assert!(!s.client.has_role(&Role::Resolver, &creator));
+    assert!(s.client.has_role(&Role::Treasurer, &s.admin));
+    assert_eq!(s.client.get_admin(), Some(s.admin.clone()));
+
+    let market_id = create(&creator).unwrap().unwrap();
+    assert_eq!(
+        s.client.try_resolve_market_manual_only(&creator, &market_id, &1_100_000),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        s.client.try_withdraw_commission(&resolver, &s.betting_token, &resolver, &1),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    // Resolver çözer; otomatik yeniden başlayan market orijinal creator'da kalır
+    s.client.resolve_and_payout_market(&resolver, &market_id);
+    let restarted = s.client.get_market(&(market_id + 1)).unwrap();
+    assert_eq!(restarted.creator, creator);
+
+    s.client.revoke_role(&s.admin, &Role::MarketCreator, &creator);
+    assert_eq!(create(&creator), Err(Ok(Error::NotAuthorized)));
+}
+
+#[test]
+fn test_manual_claim_moves_tokens() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let up_user = bet(&s, market_id, 1_000, 0);
+    let down_user = bet(&s, market_id, 3_000, 1);
+
+    s.client.resolve_market_manual_only(&s.admin, &market_id, &1_100_000);
+    assert_eq!(s.client.calculate_user_winnings(&market_id, &up_user), 4_000);
+
+    assert_eq!(s.client.claim_winnings(&up_user, &market_id), 4_000);
+    assert_eq!(token_client.balance(&up_user), 4_000);
+    assert_eq!(
+        s.client.try_claim_winnings(&up_user, &market_id),
+        Err(Ok(Error::UserAlreadyPaidOut))
+    );
+    assert_eq!(s.client.claim_winnings(&down_user, &market_id), 0);
+    assert_eq!(token_client.balance(&s.client.address), 0);
+
+    let summary = s.client.process_payouts_manual(&s.admin, &market_id);
+    assert_eq!(summary.total_paid_amount, 4_000);
+    // Ödeme yapmayan kapatma SETTLED yayınlamaz
+    assert!(contract_events(&s).is_empty());
+}
+
+#[test]
+fn test_refund_when_no_winners() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let up_user = bet(&s, market_id, 1_000, 0);
+    let down_user = bet(&s, market_id, 2_000, 1);
+
+    // Stable kazanır ama kimse Stable'a oynamadı
+    s.client.resolve_market_manual_only(&s.admin, &market_id, &1_000_000);
+    assert!(s.client.check_needs_refund(&market_id));
+
+    let summary = s.client.refund_no_winners_market(&s.admin, &market_id);
+    assert_eq!(summary.total_paid_amount, 3_000);
+    assert_eq!(token_client.balance(&up_user), 1_000);
+    assert_eq!(token_client.balance(&down_user), 2_000);
+    assert!(!s.client.check_needs_refund(&market_id));
+}
+
+#[test]
+fn test_auto_payout_refunds_when_no_winners() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let up_user = bet(&s, market_id, 1_000, 0);
+    let down_user = bet(&s, market_id, 2_000, 1);
+
+    // Otomatik ödeme turu Stable'ın boş havuzunu görür ve herkese iade eder
+    let summary = s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(summary.total_paid_amount, 3_000);
+    assert_eq!(token_client.balance(&up_user), 1_000);
+    assert_eq!(token_client.balance(&down_user), 2_000);
+    assert_eq!(token_client.balance(&s.client.address), 0);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Settled);
+    assert!(!s.client.check_needs_refund(&market_id));
+
+    // Manuel kapatma da stake'leri kilitli bırakmadan iade eder
+    let manual_market = create_market(&s, &SettlementMode::Spot);
+    let up_user = bet(&s, manual_market, 1_000, 0);
+    bet(&s, manual_market, 2_000, 1);
+    s.client.resolve_market_manual_only(&s.admin, &manual_market, &1_000_000);
+    let summary = s.client.process_payouts_manual(&s.admin, &manual_market);
+    assert_eq!(summary.total_paid_amount, 3_000);
+    assert_eq!(token_client.balance(&up_user), 1_000);
+    assert_eq!(token_client.balance(&s.client.address), 0);
+}
+
+fn dispute_setup<'a>() -> (Setup<'a>, u64, Address, Address) {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    s.client.set_dispute_config(&s.admin, &86_400, &500);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+
+    let up_user = bet(&s, market_id, 1_000, 0);
+    let down_user = bet(&s, market_id, 1_000, 1);
+
+    // Admin yanlış fiyatla Up ilan eder; oracle kaydı Down diyor
+    let end_time = s.client.get_market(&market_id).unwrap().end_time;
+    s.oracle.set_price_at(&end_time, &900_000);
+    s.env.ledger().with_mut(|l| l.timestamp = end_time);
+    s.client.resolve_market_manual(&s.admin, &market_id, &1_100_000);
+
+    (s, market_id, up_user, down_user)
+}
+
+#[test]
+fn test_payouts_wait_for_dispute_window() {
+    let (s, market_id, up_user, _) = dispute_setup();
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    // Otomatik ödeme yapılmadı, claim bekler
+    assert_eq!(token_client.balance(&up_user), 0);
+    assert_eq!(
+        s.client.try_claim_winnings(&up_user, &market_id),
+        Err(Ok(Error::DisputeWindowOpen))
+    );
+
+    s.env.ledger().with_mut(|l| l.timestamp += 86_400);
+    let challenger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_dispute_resolution(&challenger, &market_id),
+        Err(Ok(Error::DisputeWindowClosed))
+    );
+
+    assert_eq!(
+        s.client.try_process_payouts_improved(&up_user, &market_id),
+        Err(Ok(Error::NotAuthorized))
+    );
+    s.client.process_payouts_improved(&s.admin, &market_id);
+    assert_eq!(token_client.balance(&up_user), 2_000);
+}
+
+#[test]
+fn test_dispute_overturned_refunds_bond() {
+    let (s, market_id, up_user, down_user) = dispute_setup();
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let challenger = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&challenger, &500);
+    let dispute = s.client.dispute_resolution(&challenger, &market_id);
+    assert_eq!(dispute.evidence_price, 900_000);
+    assert_eq!(dispute.proposed_outcome, 1);
+    assert_eq!(token_client.balance(&challenger), 0);
+    assert_eq!(s.client.get_treasury_report(&s.betting_token).locked_in_markets, 2_500);
+
+    // Açık itiraz varken süre dolsa da ödeme yok
+    s.env.ledger().with_mut(|l| l.timestamp += 86_400);
+    assert_eq!(
+        s.client.try_claim_winnings(&up_user, &market_id),
+        Err(Ok(Error::MarketDisputed))
+    );
+
+    let arbiter = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_resolve_dispute(&arbiter, &market_id, &true),
+        Err(Ok(Error::NotAuthorized))
+    );
+    s.client.grant_role(&s.admin, &Role::Arbiter, &arbiter);
+    assert_eq!(s.client.resolve_dispute(&arbiter, &market_id, &true), 1);
+    assert_eq!(token_client.balance(&challenger), 500);
+
+    assert_eq!(s.client.claim_winnings(&down_user, &market_id), 2_000);
+    assert_eq!(s.client.claim_winnings(&up_user, &market_id), 0);
+    let report = s.client.get_treasury_report(&s.betting_token);
+    assert_eq!((report.locked_in_markets, report.contract_balance, report.unallocated), (0, 0, 0));
+}
+
+#[test]
+fn test_dispute_upheld_slashes_bond() {
+    let (s, market_id, up_user, _) = dispute_setup();
+
+    let challenger = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&challenger, &500);
+    s.client.dispute_resolution(&challenger, &market_id);
+    assert_eq!(
+        s.client.try_dispute_resolution(&challenger, &market_id),
+        Err(Ok(Error::InvalidDispute))
+    );
+
+    // Admin (arbiter yetkisi dahil) sonucu onaylar: teminat hazineye
+    assert_eq!(s.client.resolve_dispute(&s.admin, &market_id, &false), 0);
+    assert_eq!(s.client.get_treasury_balance(&s.betting_token), 500);
+    assert_eq!(s.client.claim_winnings(&up_user, &market_id), 2_000);
+
+    let dispute = s.client.get_dispute(&market_id).unwrap();
+    assert!(dispute.is_settled && !dispute.overturned);
+}
+
+#[test]
+fn test_void_market_refunds_full_stake() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = s.client.create_market(
+        &s.admin,
+        &String::from_str(&s.env, "XLM 24h"),
+        &xlm_feed(&s),
+        &s.betting_token,
+        &hours(24),
+        &MarketConfig {
+            target_price: 1_000_000,
+            stable_tolerance: 100,
+            house_edge: 500, // %5 - iptalde kesilmez
+            settlement_mode: SettlementMode::Spot,
+            outcome_rule: OutcomeRule::RelativeBand,
+            pricing: PricingMode::PariMutuel,
+            limits: no_limits(),
+            liquidity: Vec::new(&s.env),
+        },
+    );
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+
+    let user = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&user, &3_000);
+    s.client.place_bet(&user, &market_id, &1_000, &0);
+    s.client.place_bet(&user, &market_id, &2_000, &1);
+    let other = bet(&s, market_id, 1_000, 2);
+
+    assert_eq!(
+        s.client.try_claim_refund(&user, &market_id),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        s.client.try_void_market(&s.admin, &market_id, &VoidReason::None),
+        Err(Ok(Error::InvalidVoidReason))
+    );
+    s.client.void_market(&s.admin, &market_id, &VoidReason::OracleOutage);
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.void_reason, VoidReason::OracleOutage);
+
+    assert_eq!(
+        s.client.try_place_bet(&other, &market_id, &1, &0),
+        Err(Ok(Error::MarketVoided))
+    );
+    assert_eq!(
+        s.client.try_resolve_market_manual_only(&s.admin, &market_id, &1_100_000),
+        Err(Ok(Error::MarketVoided))
+    );
+
+    assert_eq!(s.client.get_treasury_report(&s.betting_token).locked_in_markets, 4_000);
+    assert_eq!(s.client.claim_refund(&user, &market_id), 3_000);
+    assert_eq!(
+        s.client.try_claim_refund(&user, &market_id),
+        Err(Ok(Error::PositionNotFound))
+    );
+    assert_eq!(s.client.claim_refund(&other, &market_id), 1_000);
+    assert_eq!(token_client.balance(&user), 3_000);
+    assert_eq!(s.client.get_treasury_balance(&s.betting_token), 0);
+    assert_eq!(token_client.balance(&s.client.address), 0);
+}
+
+#[test]
+fn test_void_fixed_odds_market_returns_bankroll() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let pricing = fixed_odds(&s, &[1_900, 1_900], 10_000);
+    let market_id =
+        create_market_with_pricing(&s, &SettlementMode::Spot, &OutcomeRule::Strike, &pricing);
+    let token_client = token::Client::new(&s.env, &s.betting_token);
+    fund(&s, market_id, 5_000);
+
+    let user = bet(&s, market_id, 1_000, 0);
+    s.client.void_market(&s.admin, &market_id, &VoidReason::MisspecifiedMarket);
+
+    assert_eq!(s.client.withdraw_bankroll(&s.admin, &market_id), 5_000);
+    assert_eq!(s.client.claim_refund(&user, &market_id), 1_000);
+    assert_eq!(token_client.balance(&s.client.address), 0);
+}
+
+#[test]
+fn test_market_status_lifecycle() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let up_user = bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 1_000, 1);
+
+    assert_eq!(
+        s.client.try_claim_winnings(&up_user, &market_id),
+        Err(Ok(Error::MarketNotResolved))
+    );
+    assert_eq!(s.client.get_markets(&MarketFilter::Status(MarketStatus::Open), &0, &10).markets.len(), 1);
+
+    // end_time geçince bahis kapanır
+    let end_time = s.client.get_market(&market_id).unwrap().end_time;
+    s.env.ledger().with_mut(|l| l.timestamp = end_time);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Closed);
+    assert_eq!(s.client.get_markets(&MarketFilter::Status(MarketStatus::Open), &0, &10).markets.len(), 0);
+    assert_eq!(s.client.get_active_markets(&0, &10).markets.len(), 0);
+
+    s.client.set_dispute_config(&s.admin, &3_600, &0);
+    s.client.resolve_market_manual_only(&s.admin, &market_id, &1_100_000);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Resolving);
+    assert_eq!(
+        s.client.try_process_payouts_manual(&s.admin, &market_id),
+        Err(Ok(Error::DisputeWindowOpen))
+    );
+    assert_eq!(
+        s.client.try_void_market(&s.admin, &market_id, &VoidReason::Other),
+        Err(Ok(Error::MarketAlreadyResolved))
+    );
+
+    s.env.ledger().with_mut(|l| l.timestamp += 3_600);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Resolved);
+    assert_eq!(s.client.get_markets(&MarketFilter::Status(MarketStatus::Resolved), &0, &10).markets.len(), 1);
+
+    s.client.process_payouts_improved(&s.admin, &market_id);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Settled);
+    assert_eq!(
+        s.client.try_process_payouts_improved(&s.admin, &market_id),
+        Err(Ok(Error::MarketAlreadyPaidOut))
+    );
+}
+
+#[test]
+fn test_dispute_moves_market_through_disputed() {
+    let (s, market_id, _, _) = dispute_setup();
+    let challenger = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&challenger, &500);
+
+    s.client.dispute_resolution(&challenger, &market_id);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Disputed);
+    assert_eq!(s.client.get_markets(&MarketFilter::Status(MarketStatus::Disputed), &0, &10).markets.len(), 1);
+
+    s.client.resolve_dispute(&s.admin, &market_id, &true);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Resolved);
+}
+
+#[test]
+fn test_scheduled_market_opens_at_start_time_and_closes_before_end() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    s.env.ledger().with_mut(|l| l.timestamp = 10_000);
+    let create = |schedule: &MarketSchedule| {
+        s.client.try_create_market(
+            &s.admin,
+            &String::from_str(&s.env, "XLM tomorrow"),
+            &xlm_feed(&s),
+            &s.betting_token,
+            schedule,
+            &MarketConfig {
+                target_price: 1_000_000,
+                stable_tolerance: 100,
+                house_edge: 0,
+                settlement_mode: SettlementMode::Spot,
+                outcome_rule: OutcomeRule::RelativeBand,
+                pricing: PricingMode::PariMutuel,
+                limits: no_limits(),
+                liquidity: Vec::new(&s.env),
+            },
+        )
+    };
+
+    // Geçmişte başlayan ya da kapanışı süreyi aşan market reddedilir
+    let past = MarketSchedule {
+        start_time: 5_000,
+        duration_hours: 24,
+        betting_cutoff_secs: 0,
+        auto_restart: false,
+        series_id: None,
+    };
+    assert_eq!(create(&past), Err(Ok(Error::InvalidSchedule)));
+    let cutoff_too_long = MarketSchedule {
+        start_time: 0,
+        duration_hours: 1,
+        betting_cutoff_secs: 3_600,
+        auto_restart: false,
+        series_id: None,
+    };
+    assert_eq!(create(&cutoff_too_long), Err(Ok(Error::InvalidSchedule)));
+    // Seri turları yalnızca seriden açılır
+    let fake_round = MarketSchedule {
+        series_id: Some(1),
+        ..hours(24)
+    };
+    assert_eq!(create(&fake_round), Err(Ok(Error::InvalidSchedule)));
+
+    let start_time = 20_000;
+    let schedule = MarketSchedule {
+        start_time,
+        duration_hours: 24,
+        betting_cutoff_secs: 1_800,
+        auto_restart: false,
+        series_id: None,
+    };
+    let market_id = create(&schedule).unwrap().unwrap();
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.status, MarketStatus::Scheduled);
+    assert_eq!(market.initial_price, 0);
+    assert_eq!(market.end_time, start_time + 86_400);
+    assert_eq!(market.close_time, market.end_time - 1_800);
+
+    let user = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&user, &2_000);
+    assert_eq!(
+        s.client.try_place_bet(&user, &market_id, &1_000, &0),
+        Err(Ok(Error::MarketNotStarted))
+    );
+
+    // Açılış fiyatı oluşturmadaki değil, start_time kaydı
+    s.oracle.set_price_at(&start_time, &1_200_000);
+    s.env.ledger().with_mut(|l| l.timestamp = start_time + 60);
+    s.client.place_bet(&user, &market_id, &1_000, &0);
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.status, MarketStatus::Open);
+    assert_eq!(market.initial_price, 1_200_000);
+
+    s.env.ledger().with_mut(|l| l.timestamp = market.close_time);
+    assert_eq!(s.client.get_market(&market_id).unwrap().status, MarketStatus::Closed);
+    assert_eq!(
+        s.client.try_place_bet(&user, &market_id, &1_000, &0),
+        Err(Ok(Error::MarketExpired))
+    );
+}
+
+#[test]
+fn test_series_spawns_rounds_on_cadence() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    s.env.ledger().with_mut(|l| l.timestamp = 100_000);
+    let series_id = s.client.create_series(
+        &s.admin,
+        &SeriesTemplate {
+            title: String::from_str(&s.env, "XLM daily"),
+            feed: xlm_feed(&s),
+            betting_token: s.betting_token.clone(),
+            schedule: hours(24),
+            cadence_secs: 86_400,
+            config: MarketConfig {
+                target_price: 0, // Her turun hedefi açılış fiyatı
+                stable_tolerance: 100,
+                house_edge: 0,
+                settlement_mode: SettlementMode::Spot,
+                outcome_rule: OutcomeRule::Strike,
+                pricing: PricingMode::PariMutuel,
+                limits: no_limits(),
+                liquidity: Vec::new(&s.env),
+            },
+        },
+    );
+
+    // İlk tur hemen açılır, hedef açılış fiyatı
+    let first = s.client.get_current_round(&series_id).unwrap();
+    assert_eq!(first.series_id, Some(series_id));
+    assert_eq!(first.status, MarketStatus::Open);
+    assert_eq!(first.target_price, 1_000_000);
+
+    // Sıradaki tur bir cadence sonrasına planlanır; daha ilerisi açılamaz
+    let second_id = s.client.start_next_round(&series_id);
+    let second = s.client.get_market(&second_id).unwrap();
+    assert_eq!(second.status, MarketStatus::Scheduled);
+    assert_eq!(second.start_time, first.start_time + 86_400);
+    assert_eq!(
+        s.client.try_start_next_round(&series_id),
+        Err(Ok(Error::RoundNotDue))
+    );
+
+    // İlk turun çözümü (manuel yol dahil) bir sonraki turu açar
+    s.env.ledger().with_mut(|l| l.timestamp = first.end_time);
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_market_manual_only(&s.admin, &first.id, &1_100_000);
+    let page = s.client.get_series_rounds(&series_id, &0, &2);
+    assert_eq!(page.markets.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+    let rounds = s.client.get_series_rounds(&series_id, &2, &10);
+    assert_eq!(rounds.next_cursor, None);
+    assert_eq!(rounds.markets.get(0).unwrap().start_time, second.start_time + 86_400);
+    assert_eq!(s.client.get_series(&series_id).unwrap().round_count, 3);
+
+    let current = s.client.get_current_round(&series_id).unwrap();
+    assert_eq!(current.id, second_id);
+    assert_eq!(current.target_price, 0); // Açılış fiyatı ilk işlemde alınır
+    bet(&s, second_id, 1_000, 0);
+    assert_eq!(s.client.get_market(&second_id).unwrap().target_price, 1_100_000);
+
+    // Geç kalan tetikleyici kaçırılan turları atlar
+    s.env.ledger().with_mut(|l| l.timestamp += 5 * 86_400 + 10);
+    let late_id = s.client.start_next_round(&series_id);
+    let late = s.client.get_market(&late_id).unwrap();
+    assert_eq!((late.start_time - first.start_time) % 86_400, 0);
+    assert!(late.start_time >= s.env.ledger().timestamp());
+
+    let round_count = s.client.get_series(&series_id).unwrap().round_count;
+    s.client.stop_series(&s.admin, &series_id);
+    let stopped = SeriesStoppedEvent { creator: s.admin.clone(), round_count };
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("SER_STOP"), EVENT_VERSION, series_id).into_val(&s.env),
+                stopped.into_val(&s.env),
+            ),
+        ]
+    );
+    s.env.ledger().with_mut(|l| l.timestamp = late.start_time);
+    assert_eq!(
+        s.client.try_start_next_round(&series_id),
+        Err(Ok(Error::SeriesStopped))
+    );
+}
+
+fn create_market_with_limits(s: &Setup, limits: &BetLimits) -> Result<u64, Error> {
+    let config = MarketConfig {
+        limits: limits.clone(),
+        ..base_config(s)
+    };
+    try_create_market_with_config(s, &xlm_feed(s), &config)
+}
+
+#[test]
+fn test_bet_limits_and_circuit_breaker() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let limits = BetLimits {
+        min_bet: 100,
+        max_bet: 5_000,
+        max_user_total: 6_000,
+        max_side_share_bps: 0,
+        imbalance_limit_bps: 8_000,
+        share_checks_from: 10_000,
+    };
+    assert_eq!(
+        create_market_with_limits(&s, &BetLimits { min_bet: 6_000, ..limits.clone() }),
+        Err(Error::InvalidBetLimits)
+    );
+    let market_id = create_market_with_limits(&s, &limits).unwrap();
+
+    let whale = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&whale, &10_000);
+    assert_eq!(
+        s.client.try_place_bet(&whale, &market_id, &50, &0),
+        Err(Ok(Error::BetBelowMinimum))
+    );
+    assert_eq!(
+        s.client.try_place_bet(&whale, &market_id, &5_001, &0),
+        Err(Ok(Error::BetAboveMaximum))
+    );
+
+    // Adres başına toplam sınır outcome'lar arasında toplanır
+    s.client.place_bet(&whale, &market_id, &5_000, &0);
+    s.client.place_bet(&whale, &market_id, &1_000, &1);
+    assert_eq!(
+        s.client.try_place_bet(&whale, &market_id, &100, &2),
+        Err(Ok(Error::BetLimitExceeded))
+    );
+
+    // Toplam hacim 10k altındayken dengesizlik kontrolü yok: UP 8k / 9k
+    let seller = bet(&s, market_id, 3_000, 0);
+    bet(&s, market_id, 1_500, 2);
+
+    // İlandan satın alınan pozisyon da adres başına sınıra sayılır
+    s.client.list_position(&seller, &market_id, &0, &1);
+    assert_eq!(
+        s.client.try_buy_position(&whale, &seller, &market_id, &0),
+        Err(Ok(Error::BetLimitExceeded))
+    );
+
+    // UP hacmin %80'ini aşacaksa (13k / 15.5k) UP'a bahis durur, diğer outcome'lar açık
+    let late = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&late, &5_000);
+    assert_eq!(
+        s.client.try_place_bet(&late, &market_id, &5_000, &0),
+        Err(Ok(Error::OutcomeSuspended))
+    );
+    s.client.place_bet(&late, &market_id, &5_000, &2);
+}
+
+#[test]
+fn test_side_share_limit() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market_with_limits(
+        &s,
+        &BetLimits {
+            min_bet: 0,
+            max_bet: 0,
+            max_user_total: 0,
+            max_side_share_bps: 5_000,
+            imbalance_limit_bps: 0,
+            share_checks_from: 1_000,
+        },
+    )
+    .unwrap();
+
+    // Havuz eşiğin altındayken tek başına bahis serbest
+    let first = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&first, &1_000);
+    s.client.place_bet(&first, &market_id, &800, &0);
+    let second = bet(&s, market_id, 300, 0);
+
+    // 900 / 1200 = %75 > %50
+    assert_eq!(
+        s.client.try_place_bet(&first, &market_id, &100, &0),
+        Err(Ok(Error::BetLimitExceeded))
+    );
+    // Devirde havuz değişmez: 1_100 / 1_100
+    assert_eq!(
+        s.client.try_transfer_position(&second, &first, &market_id, &0),
+        Err(Ok(Error::BetLimitExceeded))
+    );
+    s.client.place_bet(&first, &market_id, &100, &1);
+}
+
+fn create_market_with_feed(
+    s: &Setup,
+    feed: &PriceFeed,
+    outcome_rule: &OutcomeRule,
+) -> Result<u64, Error> {
+    let config = MarketConfig {
+        target_price: 60_000,
+        outcome_rule: outcome_rule.clone(),
+        ..base_config(s)
+    };
+    try_create_market_with_config(s, feed, &config)
+}
+
+#[test]
+fn test_symbol_asset_market() {
+    let s = setup();
+    s.oracle.set_spot(&58_000);
+    let btc = PriceFeed::Single(ReflectorAsset::Other(symbol_short!("BTC")));
+    let market_id = create_market_with_feed(&s, &btc, &OutcomeRule::Strike).unwrap();
+    assert_eq!(s.client.get_market(&market_id).unwrap().initial_price, 58_000);
+
+    let up_user = bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 1_000, 1);
+
+    s.oracle.set_spot(&61_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.winning_outcome, Some(0));
+    assert_eq!(market.final_price, Some(61_000));
+    assert!(s.client.get_user_bet(&market_id, &up_user, &0).unwrap().is_paid_out);
+}
+
+#[test]
+fn test_cross_pair_relative_performance() {
+    let s = setup();
+    let xlm = ReflectorAsset::Stellar(s.token.clone());
+    let btc = ReflectorAsset::Other(symbol_short!("BTC"));
+    let same_pair = PriceFeed::Cross(btc.clone(), btc.clone());
+    assert_eq!(
+        create_market_with_feed(&s, &same_pair, &OutcomeRule::RelativeBand),
+        Err(Error::InvalidPriceFeed)
+    );
+
+    // XLM/BTC oranı: XLM'in BTC'ye göre performansı
+    s.oracle.set_spot(&1_000_000); // Tek varlık fiyatı kullanılmamalı
+    s.oracle.set_cross(&200_000);
+    let feed = PriceFeed::Cross(xlm, btc);
+    assert_eq!(s.client.get_feed_price(&feed), 200_000);
+    let market_id = create_market_with_feed(&s, &feed, &OutcomeRule::RelativeBand).unwrap();
+    assert_eq!(s.client.get_market(&market_id).unwrap().initial_price, 200_000);
+
+    bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 1_000, 1);
+    bet(&s, market_id, 1_000, 2);
+
+    // Oran %3 düştü: BTC, XLM'den iyi gitti
+    s.oracle.set_cross(&194_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(s.client.get_market(&market_id).unwrap().winning_outcome, Some(1));
+}
+
+fn set_volatile_history(s: &Setup) {
+    s.env.ledger().set_timestamp(100_000);
+    let mut history = Vec::new(&s.env);
+    for price in [1_000_000i128, 1_010_000, 1_000_000, 990_000, 1_000_000] {
+        history.push_back(price);
+    }
+    s.oracle.set_history(&history);
+}
+
+#[test]
+fn test_suggest_market_params_from_volatility() {
+    let s = setup();
+    set_volatile_history(&s);
+
+    // 5 dakikalık kayıtlarda ~%1 hareket: saatte ~%3.46, dengeli tolerans %1.49
+    assert_eq!(
+        s.client.suggest_market_params(&xlm_feed(&s), &5, &1),
+        MarketSuggestion {
+            samples: 5,
+            period_secs: 300,
+            volatility_bps: 100,
+            duration_hours: 1,
+            expected_move_bps: 346,
+            stable_tolerance: 149,
+        }
+    );
+    // Süre verilmezse STABLE bandının anlamlı olduğu en kısa süre (1 saat yeterli)
+    assert_eq!(s.client.suggest_market_params(&xlm_feed(&s), &5, &0).duration_hours, 1);
+    assert_eq!(
+        s.client.try_suggest_market_params(&xlm_feed(&s), &2, &1),
+        Err(Ok(Error::InsufficientPriceHistory))
+    );
+}
+
+#[test]
+fn test_volatility_band_derives_tolerance() {
+    let s = setup();
+    set_volatile_history(&s);
+    s.oracle.set_spot(&1_000_000);
+
+    let rule = OutcomeRule::VolatilityBand(5);
+    let market_id = create_market_with_rule(&s, &SettlementMode::Spot, &rule);
+    let expected = s.client.suggest_market_params(&xlm_feed(&s), &5, &24).stable_tolerance;
+    let market = s.client.get_market(&market_id).unwrap();
+    assert_eq!(market.stable_tolerance, expected);
+    assert!(expected > 100); // Verilen %1 alt sınırdan geniş
+
+    // %2 yükseliş 24 saatlik dengeli bandın içinde kalır
+    assert_eq!(s.client.resolve_market_manual_only(&s.admin, &market_id, &1_020_000), 2);
+
+    assert_eq!(
+        create_market_with_feed(&s, &xlm_feed(&s), &OutcomeRule::VolatilityBand(2)),
+        Err(Error::InvalidOutcomeRule)
+    );
+
+    // Oracle geçmişi sonradan boşalsa da market çözülür; yalnızca yeniden başlatma atlanır
+    let restarting = s.client.create_market(
+        &s.admin,
+        &String::from_str(&s.env, "XLM 24h"),
+        &xlm_feed(&s),
+        &s.betting_token,
+        &MarketSchedule {
+            auto_restart: true,
+            ..hours(24)
+        },
+        &MarketConfig {
+            outcome_rule: rule,
+            ..base_config(&s)
+        },
+    );
+    s.oracle.set_history(&Vec::new(&s.env));
+    s.client.resolve_market_manual_only(&s.admin, &restarting, &1_000_000);
+    assert_eq!(s.client.get_market(&restarting).unwrap().status, MarketStatus::Resolved);
+    assert_eq!(s.client.get_total_markets(), restarting);
+}
+
+#[test]
+fn test_paginated_market_queries() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let first = create_market(&s, &SettlementMode::Spot);
+    let second = create_market(&s, &SettlementMode::Spot);
+    let third = create_market(&s, &SettlementMode::Spot);
+    s.client.resolve_market_manual_only(&s.admin, &second, &1_050_000);
+
+    let page = s.client.get_markets(&MarketFilter::All, &0, &2);
+    assert_eq!(page.markets.len(), 2);
+    assert_eq!(page.next_cursor, Some(third));
+    let page = s.client.get_markets(&MarketFilter::All, &third, &2);
+    assert_eq!(page.markets.get(0).unwrap().id, third);
+    assert_eq!(page.next_cursor, None);
+
+    let open = s.client.get_markets(&MarketFilter::Status(MarketStatus::Open), &0, &10);
+    assert_eq!(open.markets.len(), 2);
+    assert_eq!(open.markets.get(0).unwrap().id, first);
+    let resolved = s.client.get_markets(&MarketFilter::Status(MarketStatus::Resolved), &0, &10);
+    assert_eq!(resolved.markets.get(0).unwrap().id, second);
+
+    let by_creator = s.client.get_markets(&MarketFilter::Creator(s.admin.clone()), &0, &10);
+    assert_eq!(by_creator.markets.len(), 3);
+    let by_token = s.client.get_markets(&MarketFilter::Token(s.token.clone()), &0, &10);
+    assert!(by_token.markets.is_empty());
+    let by_series = s.client.get_markets(&MarketFilter::Series(1), &0, &10);
+    assert!(by_series.markets.is_empty());
+}
+
+#[test]
+fn test_user_history_and_top_betters() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let lost_market = create_market(&s, &SettlementMode::Spot);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+
+    let alice = bet(&s, market_id, 300, 0);
+    let bob = bet(&s, market_id, 500, 1);
+    bet(&s, market_id, 100, 0);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&alice, &200);
+    s.client.place_bet(&alice, &lost_market, &200, &1);
+    bet(&s, lost_market, 100, 0);
+
+    let page = s.client.get_top_betters(&market_id, &0, &2);
+    assert_eq!(page.next_cursor, None);
+    let top = page.betters;
+    assert_eq!(top.len(), 2);
+    assert_eq!((top.get(0).unwrap().user, top.get(0).unwrap().staked), (bob.clone(), 500));
+    assert_eq!((top.get(1).unwrap().user, top.get(1).unwrap().staked), (alice.clone(), 300));
+
+    let bets = s.client.get_market_bets(&market_id, &0, &2);
+    assert_eq!(bets.bets.len(), 2);
+    assert_eq!(bets.next_cursor, Some(2));
+    assert_eq!(s.client.get_market_bets(&market_id, &2, &2).next_cursor, None);
+
+    // Pozisyonu devreden listeden çıkar, son sıradaki kullanıcı onun sırasını alır
+    let carol = Address::generate(&s.env);
+    s.client.transfer_position(&bob, &carol, &market_id, &1);
+    let bets = s.client.get_market_bets(&market_id, &0, &10);
+    assert_eq!(bets.bets.len(), 3);
+    assert_eq!(bets.bets.get(1).unwrap().user, carol);
+    assert_eq!(bets.bets.get(2).unwrap().amount, 100);
+
+    // Çözülmemiş marketlerde P&L yok; en yeni market önce
+    let history = s.client.get_user_history(&alice, &0, &10);
+    assert_eq!(history.records.len(), 2);
+    assert_eq!(history.records.get(0).unwrap().market_id, lost_market);
+    assert_eq!(history.records.get(1).unwrap().realized_pnl, None);
+
+    s.oracle.set_spot(&1_050_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    s.client.resolve_and_payout_market(&s.admin, &lost_market);
+
+    // 900'lük havuzun 300/400'ü: 675
+    let record = s.client.get_user_history(&alice, &1, &1).records.get(0).unwrap();
+    assert_eq!((record.staked, record.returned, record.realized_pnl), (300, 675, Some(375)));
+    let record = s.client.get_user_history(&alice, &0, &1).records.get(0).unwrap();
+    assert_eq!(record.realized_pnl, Some(-200));
+    let record = s.client.get_user_history(&bob, &0, &1).records.get(0).unwrap();
+    assert_eq!(record.realized_pnl, Some(-500));
+}
+
+#[test]
+fn test_typed_resolution_and_void_events() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    bet(&s, market_id, 1_000, 0);
+    bet(&s, market_id, 500, 1);
+
+    s.client.resolve_market_manual_only(&s.admin, &market_id, &1_050_000);
+    // Son çağrının event'leri - sonraki sorgulardan önce al
+    let events = s.env.events().all();
+    let market = s.client.get_market(&market_id).unwrap();
+    let mut totals = Vec::new(&s.env);
+    for pool in market.outcomes.iter() {
+        totals.push_back(pool.total_bets);
+    }
+    let closed = MarketClosedEvent {
+        close_time: market.close_time,
+        total_volume: 1_500,
+        outcome_totals: totals,
+    };
+    let resolved = MarketResolvedEvent {
+        winning_outcome: 0,
+        initial_price: 1_000_000,
+        final_price: 1_050_000,
+        settlement_mode: SettlementMode::Spot,
+        oracle_settled: false, // Spot markette admin fiyatı
+        price_timestamp: 0,
+        resolved_at: s.env.ledger().timestamp(),
+        dispute_deadline: market.dispute_deadline,
+    };
+    assert_eq!(
+        events,
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("CLOSED"), EVENT_VERSION, market_id).into_val(&s.env),
+                closed.into_val(&s.env),
+            ),
+            (
+                s.client.address.clone(),
+                (symbol_short!("RESOLVED"), EVENT_VERSION, market_id).into_val(&s.env),
+                resolved.into_val(&s.env),
+            ),
+        ]
+    );
+
+    let voided_market = create_market(&s, &SettlementMode::Spot);
+    s.client.void_market(&s.admin, &voided_market, &VoidReason::OracleOutage);
+    let voided = MarketVoidedEvent {
+        reason: VoidReason::OracleOutage,
+        total_volume: 0,
+    };
+    assert_eq!(
+        s.env.events().all(),
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("VOIDED"), EVENT_VERSION, voided_market).into_val(&s.env),
+                voided.into_val(&s.env),
+            ),
+        ]
+    );
+
+    // Oracle çözümü fiyatın oracle kaydının zamanını taşır
+    let oracle_market = create_market(&s, &SettlementMode::Spot);
+    bet(&s, oracle_market, 1_000, 0);
+    let end_time = s.client.get_market(&oracle_market).unwrap().end_time;
+    s.env.ledger().with_mut(|l| l.timestamp = end_time);
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &oracle_market);
+    let resolved_topics: Vec<soroban_sdk::Val> =
+        (symbol_short!("RESOLVED"), EVENT_VERSION, oracle_market).into_val(&s.env);
+    let event = contract_events(&s)
+        .iter()
+        .find(|event| event.1 == resolved_topics)
+        .unwrap();
+    let resolved: MarketResolvedEvent = event.2.into_val(&s.env);
+    assert!(resolved.oracle_settled);
+    assert_eq!(resolved.final_price, 1_100_000);
+    assert_eq!(resolved.price_timestamp, end_time);
+}
+
+/// Son çağrıda kontratın yayınladığı event'ler (token transferleri hariç)
+fn contract_events(s: &Setup) -> Vec<(Address, Vec<soroban_sdk::Val>, soroban_sdk::Val)> {
+    let mut events = Vec::new(&s.env);
+    for event in s.env.events().all().iter() {
+        if event.0 == s.client.address {
+            events.push_back(event);
+        }
+    }
+    events
+}
+
+#[test]
+fn test_lmsr_trade_and_transfer_events() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = lmsr_market(&s, 100_000);
+    fund(&s, market_id, 70_000);
+    let odds = s.client.calculate_improved_odds(&market_id);
+
+    // LMSR alımı da pari-mutuel bahis gibi BET yayınlar
+    let (user, cost) = buy(&s, market_id, 0, 50_000);
+    let events = contract_events(&s);
+    let bet_placed = BetPlacedEvent {
+        user: user.clone(),
+        outcome: 0,
+        amount: cost,
+        odds_when_placed: 50_000 * 1000 / cost,
+        odds,
+        outcome_totals: vec![&s.env, cost, 0],
+    };
+    let bought = SharesTradedEvent {
+        user: user.clone(),
+        outcome: 0,
+        shares: 50_000,
+        amount: cost,
+    };
+    assert_eq!(
+        events,
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("BET"), EVENT_VERSION, market_id).into_val(&s.env),
+                bet_placed.into_val(&s.env),
+            ),
+            (
+                s.client.address.clone(),
+                (symbol_short!("BUY_SHR"), EVENT_VERSION, market_id).into_val(&s.env),
+                bought.into_val(&s.env),
+            ),
+        ]
+    );
+
+    let receiver = Address::generate(&s.env);
+    s.client.transfer_position(&user, &receiver, &market_id, &0);
+    let transferred = PositionTransferredEvent {
+        from: user,
+        to: receiver,
+        outcome: 0,
+    };
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("TRANSFER"), EVENT_VERSION, market_id).into_val(&s.env),
+                transferred.into_val(&s.env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_listing_and_admin_events() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let market_id = create_market(&s, &SettlementMode::Spot);
+    let seller = bet(&s, market_id, 1_000, 0);
+
+    s.client.list_position(&seller, &market_id, &0, &1_200);
+    s.client.cancel_listing(&seller, &market_id, &0);
+    let cancelled = ListingCancelledEvent { seller, outcome: 0 };
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("UNLISTED"), EVENT_VERSION, market_id).into_val(&s.env),
+                cancelled.into_val(&s.env),
+            ),
+        ]
+    );
+
+    s.client.set_dispute_config(&s.admin, &3_600, &500);
+    let change = ConfigChange::Dispute(DisputeConfig { window_secs: 3_600, bond: 500 });
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("CONFIG"), EVENT_VERSION, s.admin.clone()).into_val(&s.env),
+                change.into_val(&s.env),
+            ),
+        ]
+    );
+
+    s.client.set_creator_fee_share(&s.admin, &1_000);
+    let change: ConfigChange = contract_events(&s).get(0).unwrap().2.into_val(&s.env);
+    assert_eq!(change, ConfigChange::CreatorFeeShare(1_000));
+    s.client.set_referral_share(&s.admin, &2_000);
+    let change: ConfigChange = contract_events(&s).get(0).unwrap().2.into_val(&s.env);
+    assert_eq!(change, ConfigChange::ReferralShare(2_000));
+
+    let season = s.client.start_new_season(&s.admin);
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("SEASON"), EVENT_VERSION, season.id).into_val(&s.env),
+                season.into_val(&s.env),
+            ),
+        ]
+    );
+
+    let new_admin = Address::generate(&s.env);
+    s.client.transfer_admin(&s.admin, &new_admin);
+    let transferred = AdminTransferredEvent { previous_admin: s.admin.clone() };
+    assert_eq!(
+        contract_events(&s),
+        vec![
+            &s.env,
+            (
+                s.client.address.clone(),
+                (symbol_short!("ADMIN"), EVENT_VERSION, new_admin).into_val(&s.env),
+                transferred.into_val(&s.env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_referral_rewards_from_commission() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    s.client.set_referral_share(&s.admin, &2_000); // Referanslı komisyonun %20'si
+
+    let market_id = s.client.create_market(
+        &s.admin,
+        &String::from_str(&s.env, "XLM 24h"),
+        &xlm_feed(&s),
+        &s.betting_token,
+        &hours(24),
+        &MarketConfig {
+            target_price: 1_000_000,
+            stable_tolerance: 100,
+            house_edge: 500, // %5
+            settlement_mode: SettlementMode::Spot,
+            outcome_rule: OutcomeRule::RelativeBand,
+            pricing: PricingMode::PariMutuel,
+            limits: no_limits(),
+            liquidity: Vec::new(&s.env),
+        },
+    );
+    let referrer = Address::generate(&s.env);
+    let alice = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&alice, &1_000);
+    assert_eq!(
+        s.client.try_place_bet_with_referrer(&alice, &market_id, &1_000, &0, &Some(alice.clone())),
+        Err(Ok(Error::InvalidReferrer))
+    );
+    s.client.place_bet_with_referrer(&alice, &market_id, &1_000, &0, &Some(referrer.clone()));
+    bet(&s, market_id, 1_000, 1);
+
+    let stats = s.client.get_referral_stats(&referrer, &s.betting_token);
+    assert_eq!((stats.referred_bets, stats.referred_volume, stats.rewards_earned), (1, 1_000, 0));
+    assert_eq!(
+        s.client.try_claim_referral_rewards(&referrer, &s.betting_token),
+        Err(Ok(Error::InsufficientBalance))
+    );
+
+    // Komisyon 100; referanslı 1_000'in komisyonu 50, referansa 10, hazineye 90
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    let report = s.client.get_treasury_report(&s.betting_token);
+    assert_eq!(report.accrued_commission, 90);
+    assert_eq!(report.referral_rewards, 10);
+    assert_eq!(report.unallocated, 0);
+
+    assert_eq!(s.client.claim_referral_rewards(&referrer, &s.betting_token), 10);
+    assert_eq!(token::Client::new(&s.env, &s.betting_token).balance(&referrer), 10);
+    let stats = s.client.get_referral_stats(&referrer, &s.betting_token);
+    assert_eq!((stats.rewards_earned, stats.rewards_claimed), (10, 10));
+    assert_eq!(s.client.get_treasury_report(&s.betting_token).referral_rewards, 0);
+}
+
+fn seeded_config(s: &Setup, liquidity: Vec<i128>) -> MarketConfig {
+    MarketConfig {
+        liquidity,
+        ..base_config(s)
+    }
+}
+
+fn seeded_market(s: &Setup, liquidity: Vec<i128>) -> u64 {
+    try_create_market_with_config(s, &xlm_feed(s), &seeded_config(s, liquidity)).unwrap()
+}
+
+#[test]
+fn test_seeded_liquidity_sets_opening_odds_and_returns_share() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    let token = token::Client::new(&s.env, &s.betting_token);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&s.admin, &1_000);
+
+    // Boş havuzda her outcome 2.0x yer tutucu gösterir; tohumla oranlar ilk bahisten önce anlamlı
+    let empty_market = create_market(&s, &SettlementMode::Spot);
+    assert_eq!(s.client.calculate_improved_odds(&empty_market), vec![&s.env, 2000, 2000, 2000]);
+    assert_eq!(
+        try_create_market_with_config(&s, &xlm_feed(&s), &seeded_config(&s, vec![&s.env, 600, 400])),
+        Err(Error::InvalidPrediction)
+    );
+    let market_id = seeded_market(&s, vec![&s.env, 600, 300, 100]);
+    assert_eq!(token.balance(&s.admin), 0);
+    assert_eq!(s.client.calculate_improved_odds(&market_id), vec![&s.env, 1666, 3333, 5000]);
+    assert_eq!(s.client.get_market(&market_id).unwrap().total_betters(), 0);
+
+    let alice = bet(&s, market_id, 400, 0);
+    bet(&s, market_id, 200, 2);
+
+    // Kazanan havuz 1_000 (tohum 600 + alice 400), ödül havuzu 1_600
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &market_id);
+    assert_eq!(token.balance(&alice), 640);
+    assert_eq!(token.balance(&s.admin), 960);
+    assert_eq!(
+        s.client.try_withdraw_liquidity(&s.admin, &market_id),
+        Err(Ok(Error::InsufficientBalance))
+    );
+
+    let report = s.client.get_treasury_report(&s.betting_token);
+    assert_eq!((report.locked_in_markets, report.contract_balance, report.unallocated), (0, 0, 0));
+}
+
+#[test]
+fn test_voided_market_returns_seeded_liquidity() {
+    let s = setup();
+    s.oracle.set_spot(&1_000_000);
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(&s.admin, &300);
+    let market_id = seeded_market(&s, vec![&s.env, 100, 100, 100]);
+    bet(&s, market_id, 50, 1);
+
+    s.client.void_market(&s.admin, &market_id, &VoidReason::OracleOutage);
+    assert_eq!(s.client.get_treasury_report(&s.betting_token).unallocated, 0);
+    assert_eq!(s.client.withdraw_liquidity(&s.admin, &market_id), 300);
+    assert_eq!(token::Client::new(&s.env, &s.betting_token).balance(&s.admin), 300);
+    assert_eq!(s.client.get_treasury_report(&s.betting_token).unallocated, 0);
+}
+
+fn place(s: &Setup, user: &Address, market_id: u64, amount: i128, prediction: u32) {
+    token::StellarAssetClient::new(&s.env, &s.betting_token).mint(user, &amount);
+    s.client.place_bet(user, &market_id, &amount, &prediction);
+}
+
+fn stats(markets_played: u32, wins: u32, volume: i128, net_pnl: i128, points: u32) -> UserStats {
+    UserStats { markets_played, wins, volume, net_pnl, points }
+}
+
+#[test]
+fn test_lifetime_stats_and_season_leaderboard() {
+    let s = setup();
+    let alice = Address::generate(&s.env);
+    let bob = Address::generate(&s.env);
+
+    // Sezon 1: otomatik ödeme turu kaybedenleri de işler
+    s.oracle.set_spot(&1_000_000);
+    let first = create_market(&s, &SettlementMode::Spot);
+    place(&s, &alice, first, 300, 0);
+    place(&s, &bob, first, 100, 1);
+    s.oracle.set_spot(&1_100_000);
+    s.client.resolve_and_payout_market(&s.admin, &first);
+
+    assert_eq!(s.client.get_user_stats(&alice, &s.betting_token), stats(1, 1, 300, 100, 30));
+    let board = s.client.get_leaderboard(&1, &s.betting_token, &10);
+    assert_eq!(board.len(), 2);
+    assert_eq!(board.get(0).unwrap().user, alice);
+    assert_eq!(board.get(1).unwrap().stats, stats(1, 0, 100, -100, 10));
+
+    assert_eq!(
+        s.client.try_start_new_season(&bob),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(s.client.start_new_season(&s.admin).id, 2);
+
+    // Sezon 2: claim yolu - sonuç her kullanıcı için bir kez işlenir
+    s.oracle.set_spot(&1_000_000);
+    let second = create_market(&s, &SettlementMode::Spot);
+    place(&s, &alice, second, 100, 1);
+    place(&s, &bob, second, 400, 0);
+    s.client.resolve_market_manual_only(&s.admin, &second, &1_100_000);
+    assert_eq!(s.client.claim_winnings(&bob, &second), 500);
+    assert_eq!(s.client.claim_winnings(&alice, &second), 0);
+    assert_eq!(s.client.claim_winnings(&alice, &second), 0);
+
+    let board = s.client.get_leaderboard(&2, &s.betting_token, &1);
+    assert_eq!(board.len(), 1);
+    assert_eq!(board.get(0).unwrap().user, bob);
+    assert_eq!(board.get(0).unwrap().stats, stats(1, 1, 400, 100, 30));
+    assert_eq!(s.client.get_user_stats(&alice, &s.betting_token), stats(2, 1, 400, 0, 40));
+    assert_eq!(s.client.get_leaderboard(&1, &s.betting_token, &1).get(0).unwrap().user, alice);
+
+    // İade yolları da sonucu işler: kazanansız market ve iptal
+    let third = create_market(&s, &SettlementMode::Spot);
+    place(&s, &alice, third, 200, 0);
+    s.oracle.set_spot(&1_000_000);
+    s.client.resolve_and_payout_market(&s.admin, &third);
+    assert_eq!(s.client.get_user_stats(&alice, &s.betting_token), stats(3, 1, 600, 0, 50));
+
+    let voided = create_market(&s, &SettlementMode::Spot);
+    place(&s, &bob, voided, 50, 1);
+    s.client.void_market(&s.admin, &voided, &VoidReason::OracleOutage);
+    assert_eq!(s.client.claim_refund(&bob, &voided), 50);
+    assert_eq!(s.client.get_user_stats(&bob, &s.betting_token), stats(3, 1, 550, 0, 50));
+
+    let board = s.client.get_leaderboard(&2, &s.betting_token, &10);
+    assert_eq!(board.len(), 2);
+    assert_eq!(board.get(0).unwrap().stats, stats(2, 1, 450, 100, 40));
+    assert_eq!(board.get(1).unwrap().user, alice);
+    assert_eq!(board.get(1).unwrap().stats, stats(2, 0, 300, -100, 20));
+}
@@ -7,12 +7,15 @@ echo "Building contract..."
 cd bet-prediction-market
 cargo build --target wasm32-unknown-unknown --release
 
-# Deploy contract using soroban CLI
+# Deploy contract using soroban CLI - the admin is set by the constructor at deploy time
 echo "Deploying contract..."
+ADMIN_ADDRESS=${ADMIN_ADDRESS:-$(soroban keys address alice)}
 soroban contract deploy \
     --wasm target/wasm32-unknown-unknown/release/bet_prediction.wasm \
     --source alice \
-    --network testnet
+    --network testnet \
+    -- \
+    --admin "$ADMIN_ADDRESS"
 
 echo "Contract deployed successfully!"
 
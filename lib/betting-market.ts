@@ -8,60 +8,73 @@ import {
   BASE_FEE,
   Address,
   scValToNative,
-  nativeToScVal
+  nativeToScVal,
+  xdr
 } from '@stellar/stellar-sdk'
 import { WalletService } from './wallet'
 import { i128 } from '@stellar/stellar-sdk/contract'
 import { SecureStellarManager } from './stellar-manager-secure'
 
+export type MarketStatus =
+  | 'Scheduled'
+  | 'Open'
+  | 'Closed'
+  | 'Resolving'
+  | 'Disputed'
+  | 'Resolved'
+  | 'Settled'
+  | 'Voided'
+
+export interface OutcomePool {
+  label: string
+  total_bets: bigint
+  betters_count: number
+  liability: bigint
+}
+
 export interface Market {
   id: bigint
   title: string
-  token: string
+  feed_asset: string // Token address of a Single(Stellar) feed, empty for other feeds
   betting_token: string
+  creator: string
   initial_price: bigint
   target_price: bigint
   stable_tolerance: bigint
   start_time: bigint
   end_time: bigint
-  total_up_bets: bigint
-  total_down_bets: bigint
-  total_stable_bets: bigint
-  up_betters_count: number
-  down_betters_count: number
-  stable_betters_count: number
-  is_resolved: boolean
-  is_paid_out: boolean
-  winning_side?: 'Up' | 'Down' | 'Stable'
+  close_time: bigint
+  outcomes: OutcomePool[] // index = outcome
+  status: MarketStatus
+  winning_outcome?: number
   final_price?: bigint
   auto_restart: boolean
   restart_duration: bigint
   house_edge: bigint
+  series_id?: bigint
+}
+
+export interface BetEntry {
+  amount: bigint
+  timestamp: bigint
+  odds_when_placed: bigint
 }
 
 export interface UserBet {
   user: string
   market_id: bigint
   amount: bigint
-  prediction: 'Up' | 'Down' | 'Stable'
-  timestamp: bigint
-  odds_when_placed: bigint
+  outcome: number // Index into Market.outcomes
+  entries: BetEntry[]
   is_paid_out: boolean
   winnings: bigint
-}
-
-export interface Odds {
-  up_odds: bigint
-  down_odds: bigint
-  stable_odds: bigint
+  shares: bigint
 }
 
 export interface MarketStats {
   total_volume: bigint
   total_betters: number
-  up_percentage: bigint
-  down_percentage: bigint
-  stable_percentage: bigint
+  outcome_percentages: bigint[] // Basis points, in outcome index order
   prize_pool: bigint
   house_commission: bigint
 }
@@ -73,6 +86,52 @@ export interface PayoutSummary {
   remaining_balance: bigint
 }
 
+// MarketSchedule argument of create_market
+export interface MarketScheduleInput {
+  durationHours: bigint
+  startTime?: bigint // 0 / unset = now
+  bettingCutoffSecs?: bigint
+  autoRestart?: boolean
+}
+
+// MarketConfig argument of create_market - AbsoluteBand, Spot, pari-mutuel, no limits
+export interface MarketConfigInput {
+  targetPrice: bigint // 0 = opening price
+  stableTolerance: bigint
+  houseEdge: bigint // Basis points
+}
+
+const PAGE_SIZE = 50 // Contract's MAX_PAGE_SIZE
+
+// Outcome announced - same as Market::is_resolved in the contract
+export function isResolved(market: Market): boolean {
+  return ['Resolving', 'Disputed', 'Resolved', 'Settled'].includes(market.status)
+}
+
+export function outcomeLabel(market: Market, outcome: number | undefined): string {
+  if (outcome === undefined) return 'Unknown'
+  return market.outcomes[outcome]?.label ?? outcome.toString()
+}
+
+// contracttype enum with a unit or tuple variant: Vec[Symbol(variant), ...values]
+function enumVal(variant: string, ...values: xdr.ScVal[]): xdr.ScVal {
+  return xdr.ScVal.scvVec([xdr.ScVal.scvSymbol(variant), ...values])
+}
+
+// contracttype struct: ScMap sorted by field name
+function structVal(fields: Record<string, xdr.ScVal>): xdr.ScVal {
+  return xdr.ScVal.scvMap(
+    Object.keys(fields)
+      .sort()
+      .map(key => new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(key), val: fields[key] }))
+  )
+}
+
+// scValToNative returns enums as ['Variant', ...values]
+function enumTag(value: any): string {
+  return Array.isArray(value) ? String(value[0]) : String(value)
+}
+
 export class BettingMarketService {
   private server: rpc.Server
   private contract: Contract
@@ -156,21 +215,48 @@ export class BettingMarketService {
     }
   }
 
-  // Create new market
+  // Create new market on the Reflector price of `feedToken`
   async createMarket(
     admin: string,
     title: string,
-    token: string,
+    feedToken: string,
     bettingToken: string,
-    targetPrice: bigint,
-    stableTolerance: bigint,
-    durationHours: bigint,
-    autoRestart: boolean,
-    houseEdge: bigint
+    schedule: MarketScheduleInput,
+    config: MarketConfigInput
   ): Promise<{ success: boolean; marketId?: bigint; error?: string }> {
     try {
       const account = await this.server.getAccount(admin)
       
+      const feed = enumVal(
+        'Single',
+        enumVal('Stellar', nativeToScVal(Address.fromString(feedToken), { type: 'address' }))
+      )
+      const scheduleVal = structVal({
+        start_time: nativeToScVal(schedule.startTime ?? BigInt(0), { type: 'u64' }),
+        duration_hours: nativeToScVal(schedule.durationHours, { type: 'u64' }),
+        betting_cutoff_secs: nativeToScVal(schedule.bettingCutoffSecs ?? BigInt(0), { type: 'u64' }),
+        auto_restart: nativeToScVal(schedule.autoRestart ?? false, { type: 'bool' }),
+        series_id: xdr.ScVal.scvVoid()
+      })
+      const noLimit = nativeToScVal(BigInt(0), { type: 'i128' })
+      const configVal = structVal({
+        target_price: nativeToScVal(config.targetPrice, { type: 'i128' }),
+        stable_tolerance: nativeToScVal(config.stableTolerance, { type: 'i128' }),
+        house_edge: nativeToScVal(config.houseEdge, { type: 'i128' }),
+        settlement_mode: enumVal('Spot'),
+        outcome_rule: enumVal('AbsoluteBand'),
+        pricing: enumVal('PariMutuel'),
+        limits: structVal({
+          min_bet: noLimit,
+          max_bet: noLimit,
+          max_user_total: noLimit,
+          max_side_share_bps: noLimit,
+          imbalance_limit_bps: noLimit,
+          share_checks_from: noLimit
+        }),
+        liquidity: xdr.ScVal.scvVec([])
+      })
+      
       const transaction = new TransactionBuilder(account, {
         fee: BASE_FEE,
         networkPassphrase: Networks.TESTNET,
@@ -180,13 +266,10 @@ export class BettingMarketService {
             'create_market',
             nativeToScVal(Address.fromString(admin), { type: 'address' }),
             nativeToScVal(title, { type: 'string' }),
-            nativeToScVal(Address.fromString(token), { type: 'address' }),
+            feed,
             nativeToScVal(Address.fromString(bettingToken), { type: 'address' }),
-            nativeToScVal(targetPrice, { type: 'i128' }),
-            nativeToScVal(stableTolerance, { type: 'i128' }),
-            nativeToScVal(durationHours, { type: 'u64' }),
-            nativeToScVal(autoRestart, { type: 'bool' }),
-            nativeToScVal(houseEdge, { type: 'i128' })
+            scheduleVal,
+            configVal
           )
         )
         .setTimeout(60)
@@ -246,40 +329,32 @@ export class BettingMarketService {
     }
   }
 
-  // Place bet (test version without token transfer) - Using u32 for enum
+  // Place bet - `outcome` is an index into Market.outcomes
   async placeBetTest(
     user: string,
     marketId: bigint,
     amount: bigint,
-    prediction: 'Up' | 'Down' | 'Stable'
+    outcome: number
   ): Promise<{ success: boolean; error?: string }> {
     try {
-      debugger;
       const account = await this.server.getAccount(user)
       
-      // Convert string prediction to u32: Up=0, Down=1, Stable=2
-      const predictionU32 = prediction === 'Up' ? 0 : prediction === 'Down' ? 1 : 2;
-      
-      console.log('Using u32 prediction format:', predictionU32, 'for', prediction);
-      
       const transaction = new TransactionBuilder(account, {
         fee: BASE_FEE,
         networkPassphrase: Networks.TESTNET,
       })
         .addOperation(
           this.contract.call(
-            'place_bet', // Use the new u32 function
+            'place_bet',
             nativeToScVal(Address.fromString(user), { type: 'address' }),
             nativeToScVal(marketId, { type: 'u64' }),
             nativeToScVal(amount, { type: 'i128' }),
-            nativeToScVal(predictionU32, { type: 'u32' }) // Send as u32
+            nativeToScVal(outcome, { type: 'u32' })
           )
         )
         .setTimeout(60)
         .build()
 
-      console.log('Transaction built with u32 prediction, simulating...');
-      
       // Simulate first
       const simulateResponse = await this.server.simulateTransaction(transaction)
       
@@ -356,7 +431,7 @@ export class BettingMarketService {
           console.log(`✅ BettingMarketService: Market ${marketId} found:`, {
             id: market.id.toString(),
             title: market.title,
-            is_resolved: market.is_resolved,
+            status: market.status,
             end_time: market.end_time.toString()
           })
           return market
@@ -374,83 +449,24 @@ export class BettingMarketService {
     }
   }
 
-  // Get active markets
+  // Get active markets - reads every get_active_markets page
   async getActiveMarkets(): Promise<Market[]> {
     try {
-      const dummyAddress = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF'
-      const account = await this.server.getAccount(dummyAddress)
-      
-      const transaction = new TransactionBuilder(account, {
-        fee: BASE_FEE,
-        networkPassphrase: Networks.TESTNET,
-      })
-        .addOperation(this.contract.call('get_active_markets'))
-        .setTimeout(30)
-        .build()
-
-      const response = await this.server.simulateTransaction(transaction)
-      
-      if (rpc.Api.isSimulationSuccess(response)) {
-        const result = response.result?.retval
-        if (result) {
-          const nativeResult = scValToNative(result)
-          if (Array.isArray(nativeResult)) {
-            return nativeResult.map(market => this.parseMarketFromResult(market))
-          }
-        }
-      }
-      
-      return []
+      return await this.getMarketPages('get_active_markets')
     } catch (error) {
       console.error('Error getting active markets:', error)
       return []
     }
   }
 
-  // Get resolved markets by checking individual market IDs with optimization
+  // Get resolved markets - pages through all markets and keeps the resolved ones
   async getResolvedMarkets(): Promise<Market[]> {
     try {
-      const resolvedMarkets: Market[] = []
-      
-      // Get total markets count
-      const totalMarkets = await this.getTotalMarkets()
-      const totalMarketsNum = Number(totalMarkets) // Convert to number for arithmetic
-      console.log(`Checking ${totalMarketsNum} markets for resolved status...`)
-      
-      // Use Promise.allSettled for parallel fetching with limited concurrency
-      const batchSize = 5 // Process 5 markets at a time to avoid overwhelming
-      const batches: Promise<void>[] = []
-      
-      for (let i = 1; i <= totalMarketsNum; i += batchSize) {
-        const batch = Array.from({ length: Math.min(batchSize, totalMarketsNum - i + 1) }, (_, idx) => i + idx)
-        
-        const batchPromise = Promise.allSettled(
-          batch.map(async (marketId) => {
-            try {
-              const market = await this.getMarket(BigInt(marketId))
-              if (market && market.is_resolved) {
-                resolvedMarkets.push(market)
-              }
-            } catch (error) {
-              // Skip markets that don't exist or can't be accessed
-              console.log(`Skipping market ${marketId}:`, error)
-            }
-          })
-        ).then(() => {
-          // Log progress
-          console.log(`Processed markets ${i} to ${Math.min(i + batchSize - 1, totalMarketsNum)}`)
-        })
-        
-        batches.push(batchPromise)
-      }
-      
-      // Wait for all batches to complete
-      await Promise.all(batches)
+      const markets = await this.getMarketPages('get_markets', enumVal('All'))
+      const resolvedMarkets = markets.filter(isResolved)
       
       // Sort by end time (most recent first)
       resolvedMarkets.sort((a, b) => Number(b.end_time) - Number(a.end_time))
-      
-      console.log(`Found ${resolvedMarkets.length} resolved markets out of ${totalMarketsNum} total`)
       return resolvedMarkets
     } catch (error) {
       console.error('Error getting resolved markets:', error)
@@ -458,8 +474,48 @@ export class BettingMarketService {
     }
   }
 
-  // Calculate odds for market
-  async calculateOdds(marketId: bigint): Promise<Odds | null> {
+  // Follow a paged market query until next_cursor runs out
+  private async getMarketPages(method: string, ...args: xdr.ScVal[]): Promise<Market[]> {
+    const dummyAddress = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF'
+    const account = await this.server.getAccount(dummyAddress)
+    const markets: Market[] = []
+    let cursor: bigint | undefined = BigInt(0)
+    
+    while (cursor !== undefined) {
+      const transaction = new TransactionBuilder(account, {
+        fee: BASE_FEE,
+        networkPassphrase: Networks.TESTNET,
+      })
+        .addOperation(
+          this.contract.call(
+            method,
+            ...args,
+            nativeToScVal(cursor, { type: 'u64' }),
+            nativeToScVal(PAGE_SIZE, { type: 'u32' })
+          )
+        )
+        .setTimeout(30)
+        .build()
+
+      const response = await this.server.simulateTransaction(transaction)
+      if (!rpc.Api.isSimulationSuccess(response) || !response.result?.retval) {
+        break
+      }
+      
+      const page = scValToNative(response.result.retval) as any
+      for (const market of page.markets || []) {
+        markets.push(this.parseMarketFromResult(market))
+      }
+      cursor = page.next_cursor === undefined || page.next_cursor === null
+        ? undefined
+        : BigInt(page.next_cursor)
+    }
+    
+    return markets
+  }
+
+  // Calculate odds for market - in outcome index order (1000 = 1.0x)
+  async calculateOdds(marketId: bigint): Promise<bigint[] | null> {
     try {
       const dummyAddress = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF'
       const account = await this.server.getAccount(dummyAddress)
@@ -482,12 +538,8 @@ export class BettingMarketService {
       if (rpc.Api.isSimulationSuccess(response)) {
         const result = response.result?.retval
         if (result) {
-          const nativeResult = scValToNative(result) as any
-          return {
-            up_odds: BigInt(nativeResult.up_odds || 0),
-            down_odds: BigInt(nativeResult.down_odds || 0),
-            stable_odds: BigInt(nativeResult.stable_odds || 0)
-          }
+          const nativeResult = scValToNative(result) as any[]
+          return nativeResult.map(odds => BigInt(odds || 0))
         }
       }
       
@@ -498,8 +550,8 @@ export class BettingMarketService {
     }
   }
 
-  // Get user bet
-  async getUserBet(marketId: bigint, user: string): Promise<UserBet | null> {
+  // Get user bet - the user's position on a single outcome
+  async getUserBet(marketId: bigint, user: string, outcome: number): Promise<UserBet | null> {
     try {
       const dummyAddress = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF'
       const account = await this.server.getAccount(dummyAddress)
@@ -512,7 +564,8 @@ export class BettingMarketService {
           this.contract.call(
             'get_user_bet',
             nativeToScVal(marketId, { type: 'u64' }),
-            nativeToScVal(Address.fromString(user), { type: 'address' })
+            nativeToScVal(Address.fromString(user), { type: 'address' }),
+            nativeToScVal(outcome, { type: 'u32' })
           )
         )
         .setTimeout(30)
@@ -535,6 +588,43 @@ export class BettingMarketService {
     }
   }
 
+  // All of the user's positions in a market (one per outcome)
+  async getUserPositions(marketId: bigint, user: string): Promise<UserBet[]> {
+    try {
+      const dummyAddress = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF'
+      const account = await this.server.getAccount(dummyAddress)
+      
+      const transaction = new TransactionBuilder(account, {
+        fee: BASE_FEE,
+        networkPassphrase: Networks.TESTNET,
+      })
+        .addOperation(
+          this.contract.call(
+            'get_user_positions',
+            nativeToScVal(marketId, { type: 'u64' }),
+            nativeToScVal(Address.fromString(user), { type: 'address' })
+          )
+        )
+        .setTimeout(30)
+        .build()
+
+      const response = await this.server.simulateTransaction(transaction)
+      
+      if (rpc.Api.isSimulationSuccess(response)) {
+        const result = response.result?.retval
+        if (result) {
+          const nativeResult = scValToNative(result) as any[]
+          return nativeResult.map(position => this.parseUserBetFromResult(position))
+        }
+      }
+      
+      return []
+    } catch (error) {
+      console.error('Error getting user positions:', error)
+      return []
+    }
+  }
+
   // Get market stats
   async getMarketStats(marketId: bigint): Promise<MarketStats | null> {
     try {
@@ -563,9 +653,7 @@ export class BettingMarketService {
           return {
             total_volume: BigInt(nativeResult.total_volume || 0),
             total_betters: Number(nativeResult.total_betters || 0),
-            up_percentage: BigInt(nativeResult.up_percentage || 0),
-            down_percentage: BigInt(nativeResult.down_percentage || 0),
-            stable_percentage: BigInt(nativeResult.stable_percentage || 0),
+            outcome_percentages: (nativeResult.outcome_percentages || []).map((pct: any) => BigInt(pct || 0)),
             prize_pool: BigInt(nativeResult.prize_pool || 0),
             house_commission: BigInt(nativeResult.house_commission || 0)
           }
@@ -660,7 +748,7 @@ export class BettingMarketService {
     admin: string,
     marketId: bigint,
     finalPrice: bigint
-  ): Promise<{ success: boolean; winningSide?: string; error?: string }> {
+  ): Promise<{ success: boolean; winningOutcome?: number; error?: string }> {
     try {
       debugger;
          const stellarManager = new SecureStellarManager();
@@ -721,19 +809,18 @@ export class BettingMarketService {
 
         if (getResponse.status === 'SUCCESS') {
           const result = getResponse.returnValue
-          let winningSide = 'Unknown'
+          let winningOutcome: number | undefined = undefined
           
           if (result) {
             try {
-              const nativeResult = scValToNative(result)
-              winningSide = nativeResult as string
-              console.log(`Market resolved successfully, winning side: ${winningSide}`)
+              winningOutcome = Number(scValToNative(result))
+              console.log(`Market resolved successfully, winning outcome: ${winningOutcome}`)
             } catch (e) {
-              console.warn('Could not parse winning side from result:', e)
+              console.warn('Could not parse winning outcome from result:', e)
             }
           }
 
-          return { success: true, winningSide }
+          return { success: true, winningOutcome }
         } else {
           const errorMsg = `Transaction failed with status: ${getResponse.status}`
           console.error(errorMsg)
@@ -785,90 +872,60 @@ export class BettingMarketService {
 
   // Helper methods for parsing contract results
   private parseMarketFromResult(data: any): Market {
-    // Safe parsing for winning_side enum
-    let winningSide: 'Up' | 'Down' | 'Stable' | undefined = undefined;
+    // PriceFeed::Single(Asset::Stellar(address)) -> ['Single', ['Stellar', address]]
+    const feed = Array.isArray(data.feed) ? data.feed : []
+    const asset = feed[0] === 'Single' && Array.isArray(feed[1]) ? feed[1] : []
     
-    if (data.winning_side !== undefined && data.winning_side !== null) {
-      // Handle different possible enum formats
-      if (typeof data.winning_side === 'string') {
-        winningSide = data.winning_side as 'Up' | 'Down' | 'Stable';
-      } else if (typeof data.winning_side === 'number') {
-        // Handle numeric format: 0=Up, 1=Down, 2=Stable
-        switch (data.winning_side) {
-          case 0: winningSide = 'Up'; break;
-          case 1: winningSide = 'Down'; break;
-          case 2: winningSide = 'Stable'; break;
-        }
-      } else if (typeof data.winning_side === 'object') {
-        // Handle instance format: { "Up": {} } or { "tag": "Up" }
-        if (data.winning_side.tag) {
-          winningSide = data.winning_side.tag as 'Up' | 'Down' | 'Stable';
-        } else {
-          // Find the key in the object
-          const keys = Object.keys(data.winning_side);
-          if (keys.length > 0) {
-            winningSide = keys[0] as 'Up' | 'Down' | 'Stable';
-          }
-        }
-      }
-    }
+    const outcomes: OutcomePool[] = (data.outcomes || []).map((pool: any) => ({
+      label: String(pool.label ?? ''),
+      total_bets: BigInt(pool.total_bets || 0),
+      betters_count: Number(pool.betters_count || 0),
+      liability: BigInt(pool.liability || 0)
+    }))
     
     return {
       id: BigInt(data.id || 0),
       title: data.title || '',
-      token: data.token || '',
+      feed_asset: asset[0] === 'Stellar' ? String(asset[1]) : '',
       betting_token: data.betting_token || '',
+      creator: data.creator || '',
       initial_price: BigInt(data.initial_price || 0),
       target_price: BigInt(data.target_price || 0),
       stable_tolerance: BigInt(data.stable_tolerance || 0),
       start_time: BigInt(data.start_time || 0),
       end_time: BigInt(data.end_time || 0),
-      total_up_bets: BigInt(data.total_up_bets || 0),
-      total_down_bets: BigInt(data.total_down_bets || 0),
-      total_stable_bets: BigInt(data.total_stable_bets || 0),
-      up_betters_count: Number(data.up_betters_count || 0),
-      down_betters_count: Number(data.down_betters_count || 0),
-      stable_betters_count: Number(data.stable_betters_count || 0),
-      is_resolved: Boolean(data.is_resolved),
-      is_paid_out: Boolean(data.is_paid_out),
-      winning_side: winningSide,
-      final_price: data.final_price ? BigInt(data.final_price) : undefined,
+      close_time: BigInt(data.close_time || 0),
+      outcomes,
+      status: enumTag(data.status) as MarketStatus,
+      winning_outcome: data.winning_outcome === undefined || data.winning_outcome === null
+        ? undefined
+        : Number(data.winning_outcome),
+      final_price: data.final_price === undefined || data.final_price === null
+        ? undefined
+        : BigInt(data.final_price),
       auto_restart: Boolean(data.auto_restart),
       restart_duration: BigInt(data.restart_duration || 0),
-      house_edge: BigInt(data.house_edge || 0)
+      house_edge: BigInt(data.house_edge || 0),
+      series_id: data.series_id === undefined || data.series_id === null
+        ? undefined
+        : BigInt(data.series_id)
     }
   }
 
   private parseUserBetFromResult(data: any): UserBet {
-    // Safe parsing for prediction enum
-    let prediction: 'Up' | 'Down' | 'Stable' = 'Up'; // Default value
-    
-    if (data.prediction) {
-      if (typeof data.prediction === 'string') {
-        prediction = data.prediction as 'Up' | 'Down' | 'Stable';
-      } else if (typeof data.prediction === 'object') {
-        // Handle instance format: { "Up": {} } or { "tag": "Up" }
-        if (data.prediction.tag) {
-          prediction = data.prediction.tag as 'Up' | 'Down' | 'Stable';
-        } else {
-          // Find the key in the object
-          const keys = Object.keys(data.prediction);
-          if (keys.length > 0) {
-            prediction = keys[0] as 'Up' | 'Down' | 'Stable';
-          }
-        }
-      }
-    }
-    
     return {
       user: data.user || '',
       market_id: BigInt(data.market_id || 0),
       amount: BigInt(data.amount || 0),
-      prediction: prediction,
-      timestamp: BigInt(data.timestamp || 0),
-      odds_when_placed: BigInt(data.odds_when_placed || 0),
+      outcome: Number(data.outcome || 0),
+      entries: (data.entries || []).map((entry: any) => ({
+        amount: BigInt(entry.amount || 0),
+        timestamp: BigInt(entry.timestamp || 0),
+        odds_when_placed: BigInt(entry.odds_when_placed || 0)
+      })),
       is_paid_out: Boolean(data.is_paid_out),
-      winnings: BigInt(data.winnings || 0)
+      winnings: BigInt(data.winnings || 0),
+      shares: BigInt(data.shares || 0)
     }
   }
 